@@ -0,0 +1,21 @@
+use std::{env, fs, path::PathBuf};
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let output_path = out_dir.join("circuits.rs");
+    let artifacts = ["../circuits/target/product.json", "../circuits/target/codegen.json"];
+
+    // Watch the artifacts even when they are missing, so the bindings are generated once they are compiled
+    println!("cargo::rustc-check-cfg=cfg(circuit_bindings)");
+    for artifact in artifacts {
+        println!("cargo:rerun-if-changed={}", artifact);
+    }
+
+    match noir_rs::codegen::write_bindings(&artifacts, &output_path, false) {
+        Ok(_) => println!("cargo:rustc-cfg=circuit_bindings"),
+        Err(e) => {
+            println!("cargo:warning=Skipping the bindings tests, run `nargo compile --workspace` in circuits/ first: {}", e);
+            fs::write(&output_path, "").unwrap();
+        }
+    }
+}
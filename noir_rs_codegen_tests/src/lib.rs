@@ -0,0 +1,6 @@
+// The bindings generated for the test circuits, built as a crate of their own so that
+// the generated code goes through the compiler. They are empty if the circuits are not compiled.
+include!(concat!(env!("OUT_DIR"), "/circuits.rs"));
+
+#[cfg(all(test, circuit_bindings))]
+mod tests;
@@ -0,0 +1,36 @@
+use noir_rs::acir::FieldElement;
+
+use crate::{codegen, product};
+
+#[test]
+fn test_product_bindings() {
+    let inputs = product::Inputs {
+        a: FieldElement::from(5_u128),
+        b: FieldElement::from(6_u128),
+        result: FieldElement::from(30_u128),
+    };
+    let (_, outputs) = product::execute(&inputs).unwrap();
+    assert_eq!(outputs, product::PublicOutputs { result: FieldElement::from(30_u128) });
+
+    let inputs = product::Inputs { result: FieldElement::from(31_u128), ..inputs };
+    assert!(product::execute(&inputs).is_err());
+}
+
+#[test]
+fn test_struct_array_and_keyword_bindings() {
+    let inputs = codegen::Inputs {
+        r#move: codegen::Move { from: [1, 2], to: [3, 4], r#ref: 5 },
+        history: [
+            codegen::Move { from: [0, 1], to: [1, 2], r#ref: 1 },
+            codegen::Move { from: [1, 2], to: [2, 3], r#ref: 2 },
+        ],
+        r#box: 4,
+        return_value: FieldElement::from(12_u128),
+    };
+    let (_, outputs) = codegen::execute(&inputs).unwrap();
+    // The return value is renamed as a public parameter is already named `return_value`
+    assert_eq!(
+        outputs,
+        codegen::PublicOutputs { return_value: FieldElement::from(12_u128), return_value_: FieldElement::from(24_u128) }
+    );
+}
@@ -0,0 +1,126 @@
+//! Derive macros for `noir_rs::inputs`.
+//!
+//! * `#[derive(ToNoirValue)]` converts a struct into a Noir struct value (or a tuple for tuple structs)
+//! * `#[derive(NoirInputs)]` uses the fields of a struct as the named inputs of a circuit
+//!
+//! Fields can be renamed with `#[noir(rename = "name")]` and ignored with `#[noir(skip)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, LitStr};
+
+#[proc_macro_derive(ToNoirValue, attributes(noir))]
+pub fn derive_to_noir_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input, Target::Value).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[proc_macro_derive(NoirInputs, attributes(noir))]
+pub fn derive_noir_inputs(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input, Target::Inputs).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[derive(PartialEq)]
+enum Target {
+    Value,
+    Inputs,
+}
+
+struct FieldOptions {
+    rename: Option<String>,
+    skip: bool,
+}
+
+fn field_options(attrs: &[syn::Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions { rename: None, skip: false };
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("noir")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                options.rename = Some(name.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename = \"...\"` or `skip`"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+fn expand(mut input: DeriveInput, target: Target) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "only structs can be converted into Noir inputs"));
+    };
+
+    let body = match &data.fields {
+        Fields::Named(fields) => {
+            let mut inserts = Vec::new();
+            for field in &fields.named {
+                let options = field_options(&field.attrs)?;
+                if options.skip {
+                    continue;
+                }
+                let ident = field.ident.as_ref().expect("named fields have an identifier");
+                let key = options.rename.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+                inserts.push(quote! {
+                    map.insert(#key.to_string(), ::noir_rs::inputs::ToNoirValue::to_noir_value(&self.#ident));
+                });
+            }
+            quote! {
+                let mut map = ::noir_rs::inputs::serde_json::Map::new();
+                #(#inserts)*
+                ::noir_rs::inputs::serde_json::Value::Object(map)
+            }
+        }
+        Fields::Unnamed(fields) if target == Target::Value => {
+            let mut items = Vec::new();
+            for (i, field) in fields.unnamed.iter().enumerate() {
+                if field_options(&field.attrs)?.skip {
+                    continue;
+                }
+                let index = syn::Index::from(i);
+                items.push(quote! { ::noir_rs::inputs::ToNoirValue::to_noir_value(&self.#index) });
+            }
+            quote! {
+                ::noir_rs::inputs::serde_json::Value::Array(vec![#(#items),*])
+            }
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "circuit inputs must be a struct with named fields",
+            ))
+        }
+    };
+
+    // Every type parameter must itself be convertible
+    for param in input.generics.params.iter_mut() {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(::noir_rs::inputs::ToNoirValue));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(match target {
+        Target::Value => quote! {
+            impl #impl_generics ::noir_rs::inputs::ToNoirValue for #name #ty_generics #where_clause {
+                fn to_noir_value(&self) -> ::noir_rs::inputs::serde_json::Value {
+                    #body
+                }
+            }
+        },
+        Target::Inputs => quote! {
+            impl #impl_generics ::noir_rs::inputs::NoirInputs for #name #ty_generics #where_clause {
+                fn to_noir_inputs(&self) -> ::noir_rs::inputs::serde_json::Value {
+                    #body
+                }
+            }
+        },
+    })
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+
+use acvm::{
+    acir::{native_types::WitnessMap, FieldElement},
+    AcirField,
+};
+use serde_json::{Map, Value};
+
+use crate::validation::{format_input_errors, validate_inputs};
+
+pub use noirc_abi::{
+    input_parser::{Format, InputValue},
+    Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, InputMap, Sign, MAIN_RETURN_NAME,
+};
+
+/// Get the ABI from the JSON artifact of a compiled circuit
+///
+/// # Arguments
+///
+/// * circuit_json: The content of the JSON file created by `nargo compile`
+///
+/// # Returns
+///
+/// The ABI of the circuit
+pub fn get_abi(circuit_json: &str) -> Result<Abi, String> {
+    let circuit: Value = serde_json::from_str(circuit_json).map_err(|e| e.to_string())?;
+    parse_abi(&circuit["abi"])
+}
+
+/// Parse the `abi` section of a compiled circuit
+///
+/// # Arguments
+///
+/// * abi: The `abi` value of the JSON artifact
+///
+/// # Returns
+///
+/// The ABI of the circuit
+pub fn parse_abi(abi: &Value) -> Result<Abi, String> {
+    serde_json::from_value(abi.clone()).map_err(|e| format!("Failed to parse ABI: {}", e))
+}
+
+/// Parse named inputs given as a JSON object into an input map following the ABI of the circuit
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: A JSON object mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The input map, including the `return` value if one was given,
+/// or an error listing every invalid input (see `validation::validate_inputs`)
+pub fn parse_json_inputs(abi: &Abi, inputs: &Value) -> Result<InputMap, String> {
+    validate_inputs(abi, inputs).map_err(|errors| format_input_errors(&errors))?;
+    let inputs = normalize_inputs(abi, inputs);
+    Format::Json
+        .parse(&inputs.to_string(), abi)
+        .map_err(|e| format!("Failed to parse inputs: {}", e))
+}
+
+/// Parse named inputs written in TOML (e.g. the content of a `Prover.toml`)
+/// into an input map following the ABI of the circuit
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: The TOML string mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The input map, including the `return` value if one was given
+pub fn parse_toml_inputs(abi: &Abi, inputs: &str) -> Result<InputMap, String> {
+    let inputs: Value = toml::from_str(inputs).map_err(|e| format!("Failed to parse TOML inputs: {}", e))?;
+    parse_json_inputs(abi, &inputs)
+}
+
+/// Decode the public inputs and the return value of the main function from its solved witness
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * witness_map: The solved witness map of the main function
+///
+/// # Returns
+///
+/// The public inputs keyed by parameter name and the return value, if any
+pub fn decode_public_values(
+    abi: &Abi,
+    witness_map: &WitnessMap<FieldElement>,
+) -> Result<(InputMap, Option<InputValue>), String> {
+    let (input_map, return_value) = abi.decode(witness_map).map_err(|e| format!("Failed to decode witness: {}", e))?;
+    let public_inputs = input_map
+        .into_iter()
+        .filter(|(name, _)| {
+            abi.parameters.iter().any(|param| &param.name == name && param.visibility == AbiVisibility::Public)
+        })
+        .collect();
+    Ok((public_inputs, return_value))
+}
+
+/// Get the path of the ABI value held by each witness of the main function's parameters
+/// and return value, e.g. `msg[3]` or `passport.dob.year`
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+///
+/// # Returns
+///
+/// The paths keyed by witness index
+pub fn witness_paths(abi: &Abi) -> BTreeMap<u32, String> {
+    let mut paths = BTreeMap::new();
+    let mut index = 0;
+    for param in &abi.parameters {
+        flatten_paths(&param.typ, &param.name, &mut index, &mut paths);
+    }
+    if let Some(return_type) = &abi.return_type {
+        flatten_paths(&return_type.abi_type, MAIN_RETURN_NAME, &mut index, &mut paths);
+    }
+    paths
+}
+
+fn flatten_paths(typ: &AbiType, path: &str, index: &mut u32, paths: &mut BTreeMap<u32, String>) {
+    match typ {
+        AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean => {
+            paths.insert(*index, path.to_string());
+            *index += 1;
+        }
+        AbiType::String { length } => {
+            for i in 0..*length {
+                paths.insert(*index, format!("{}[{}]", path, i));
+                *index += 1;
+            }
+        }
+        AbiType::Array { length, typ } => {
+            for i in 0..*length {
+                flatten_paths(typ, &format!("{}[{}]", path, i), index, paths);
+            }
+        }
+        AbiType::Tuple { fields } => {
+            for (i, typ) in fields.iter().enumerate() {
+                flatten_paths(typ, &format!("{}.{}", path, i), index, paths);
+            }
+        }
+        AbiType::Struct { fields, .. } => {
+            for (name, typ) in fields {
+                flatten_paths(typ, &format!("{}.{}", path, name), index, paths);
+            }
+        }
+    }
+}
+
+/// Check whether the ABI type is a `BoundedVec` from the standard library
+pub(crate) fn is_bounded_vec(path: &str) -> bool {
+    path.ends_with("BoundedVec")
+}
+
+/// Rewrite the inputs so that they follow the layout expected by the ABI.
+///
+/// A `BoundedVec<T, N>` can be given as a plain array of at most `N` items,
+/// in which case it is expanded into its `storage` and `len` fields
+/// (the storage being padded with zeroes).
+fn normalize_inputs(abi: &Abi, inputs: &Value) -> Value {
+    let Value::Object(inputs) = inputs else {
+        return inputs.clone();
+    };
+    let mut normalized = inputs.clone();
+    for param in &abi.parameters {
+        if let Some(value) = inputs.get(&param.name) {
+            normalized.insert(param.name.clone(), normalize_value(&param.typ, value));
+        }
+    }
+    if let (Some(return_type), Some(value)) = (&abi.return_type, inputs.get(MAIN_RETURN_NAME)) {
+        normalized.insert(MAIN_RETURN_NAME.to_string(), normalize_value(&return_type.abi_type, value));
+    }
+    Value::Object(normalized)
+}
+
+fn normalize_value(typ: &AbiType, value: &Value) -> Value {
+    match (typ, value) {
+        (AbiType::Array { typ, .. }, Value::Array(items)) => {
+            Value::Array(items.iter().map(|item| normalize_value(typ, item)).collect())
+        }
+        (AbiType::Tuple { fields }, Value::Array(items)) => Value::Array(
+            fields.iter().zip(items).map(|(typ, item)| normalize_value(typ, item)).collect(),
+        ),
+        (AbiType::Struct { path, fields }, Value::Array(items)) if is_bounded_vec(path) => {
+            let storage_type = fields.iter().find(|(name, _)| name == "storage").map(|(_, typ)| typ);
+            match storage_type {
+                Some(AbiType::Array { length, typ }) if items.len() <= *length as usize => {
+                    let mut storage: Vec<Value> = items.iter().map(|item| normalize_value(typ, item)).collect();
+                    storage.resize(*length as usize, zero_value(typ));
+                    let mut bounded_vec = Map::new();
+                    bounded_vec.insert("storage".to_string(), Value::Array(storage));
+                    bounded_vec.insert("len".to_string(), Value::from(items.len()));
+                    Value::Object(bounded_vec)
+                }
+                // Leave it to the encoder to report the mismatch
+                _ => value.clone(),
+            }
+        }
+        (AbiType::Struct { fields, .. }, Value::Object(object)) => {
+            let mut normalized = object.clone();
+            for (name, typ) in fields {
+                if let Some(value) = object.get(name) {
+                    normalized.insert(name.clone(), normalize_value(typ, value));
+                }
+            }
+            Value::Object(normalized)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// The zero value of an ABI type, used to pad `BoundedVec` storage
+fn zero_value(typ: &AbiType) -> Value {
+    match typ {
+        AbiType::Field | AbiType::Integer { .. } => Value::from("0"),
+        AbiType::Boolean => Value::Bool(false),
+        AbiType::String { length } => Value::String("\0".repeat(*length as usize)),
+        AbiType::Array { length, typ } => Value::Array(vec![zero_value(typ); *length as usize]),
+        AbiType::Tuple { fields } => Value::Array(fields.iter().map(zero_value).collect()),
+        AbiType::Struct { fields, .. } => Value::Object(
+            fields.iter().map(|(name, typ)| (name.clone(), zero_value(typ))).collect(),
+        ),
+    }
+}
+
+/// Convert a decoded input value into JSON following its ABI type
+///
+/// Fields are written as hexadecimal strings, integers and booleans as JSON numbers
+/// and booleans (integers wider than 64 bits as decimal strings), and signed integers
+/// are decoded from their two's complement representation.
+///
+/// # Arguments
+///
+/// * value: The decoded value
+/// * typ: The ABI type of the value
+///
+/// # Returns
+///
+/// The JSON value
+pub fn input_value_to_json(value: &InputValue, typ: &AbiType) -> Value {
+    match (value, typ) {
+        (InputValue::Field(field), AbiType::Boolean) => Value::Bool(!field.is_zero()),
+        (InputValue::Field(field), AbiType::Integer { sign: Sign::Unsigned, width }) => {
+            let value = field.to_u128();
+            if *width <= 64 {
+                Value::from(value as u64)
+            } else {
+                Value::String(value.to_string())
+            }
+        }
+        (InputValue::Field(field), AbiType::Integer { sign: Sign::Signed, width }) => {
+            let mut value = field.to_u128() as i128;
+            if *width < 128 && value >= 1_i128 << (width - 1) {
+                value -= 1_i128 << width;
+            }
+            if *width <= 64 {
+                Value::from(value as i64)
+            } else {
+                Value::String(value.to_string())
+            }
+        }
+        (InputValue::Field(field), _) => Value::String(format!("0x{}", field.to_hex())),
+        (InputValue::String(string), _) => Value::String(string.clone()),
+        (InputValue::Vec(items), AbiType::Array { typ, .. }) => {
+            Value::Array(items.iter().map(|item| input_value_to_json(item, typ)).collect())
+        }
+        (InputValue::Vec(items), AbiType::Tuple { fields }) => Value::Array(
+            items.iter().zip(fields).map(|(item, typ)| input_value_to_json(item, typ)).collect(),
+        ),
+        (InputValue::Struct(map), AbiType::Struct { fields, .. }) => Value::Object(
+            fields
+                .iter()
+                .filter_map(|(name, typ)| map.get(name).map(|value| (name.clone(), input_value_to_json(value, typ))))
+                .collect(),
+        ),
+        // The value does not match its type, fall back to an untyped conversion
+        (InputValue::Vec(items), _) => Value::Array(items.iter().map(|item| input_value_to_json(item, &AbiType::Field)).collect()),
+        (InputValue::Struct(map), _) => Value::Object(
+            map.iter().map(|(name, value)| (name.clone(), input_value_to_json(value, &AbiType::Field))).collect(),
+        ),
+    }
+}
@@ -1,10 +1,12 @@
-use acvm::acir::{native_types::WitnessMap, FieldElement};
+use acvm::acir::{native_types::{WitnessMap, WitnessStack}, FieldElement};
+
+use barretenberg_rs::generated_types::ProofSystemSettings;
 
 use crate::backends::barretenberg::api::{
     self, configure_memory, proof_fields_to_bytes, settings_ultra_honk_poseidon2,
     settings_ultra_honk_keccak,
 };
-use crate::circuit::get_acir_buffer_uncompressed;
+use crate::circuit::CircuitBytecode;
 use crate::execute::execute;
 use crate::witness::serialize_witness;
 
@@ -15,7 +17,7 @@ use crate::witness::serialize_witness;
 ///
 /// # Arguments
 ///
-/// * `circuit_bytecode` - The base64-encoded circuit bytecode
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
 /// * `initial_witness` - The initial witness to use for the proof
 /// * `verification_key` - The verification key (pass empty vec for first-time proving)
 /// * `low_memory_mode` - Whether to use file-backed memory for polynomials (slower but uses less RAM)
@@ -23,32 +25,42 @@ use crate::witness::serialize_witness;
 ///
 /// # Returns
 /// * The proof as a flat byte vector
-pub fn prove_ultra_honk(
-    circuit_bytecode: &str,
+pub fn prove_ultra_honk<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
     initial_witness: WitnessMap<FieldElement>,
     verification_key: Vec<u8>,
     low_memory_mode: bool,
     max_storage_usage: Option<u64>,
 ) -> Result<Vec<u8>, String> {
-    configure_memory(low_memory_mode, max_storage_usage);
     let witness_stack = execute(circuit_bytecode, initial_witness)?;
-    let serialized_solved_witness = serialize_witness(witness_stack)?;
-    let acir_buffer_uncompressed = get_acir_buffer_uncompressed(circuit_bytecode)?;
+    prove_ultra_honk_with_witness_stack(circuit_bytecode, witness_stack, verification_key, low_memory_mode, max_storage_usage)
+}
 
+/// Generate an Ultra Honk proof for the given circuit bytecode from an already solved witness stack,
+/// e.g. one loaded from the `.gz` file written by `nargo execute` with `witness::load_witness`.
+///
+/// Uses poseidon2 as the oracle hash function (suitable for recursive verification in Noir).
+///
+/// # Arguments
+///
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * `witness_stack` - The solved witness stack
+/// * `verification_key` - The verification key (pass empty vec for first-time proving)
+/// * `low_memory_mode` - Whether to use file-backed memory for polynomials (slower but uses less RAM)
+/// * `max_storage_usage` - Optional storage budget in bytes for file-backed memory
+///
+/// # Returns
+/// * The proof as a flat byte vector
+pub fn prove_ultra_honk_with_witness_stack<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    witness_stack: WitnessStack<FieldElement>,
+    verification_key: Vec<u8>,
+    low_memory_mode: bool,
+    max_storage_usage: Option<u64>,
+) -> Result<Vec<u8>, String> {
+    configure_memory(low_memory_mode, max_storage_usage);
     let settings = settings_ultra_honk_poseidon2();
-    let response = api::circuit_prove(
-        &acir_buffer_uncompressed,
-        &serialized_solved_witness,
-        &verification_key,
-        &settings,
-    )?;
-
-    // Encode as: [num_public_inputs (4 bytes BE)] [public_inputs] [proof]
-    let num_pub = response.public_inputs.len() as u32;
-    let mut result = num_pub.to_be_bytes().to_vec();
-    result.extend(proof_fields_to_bytes(&response.public_inputs));
-    result.extend(proof_fields_to_bytes(&response.proof));
-    Ok(result)
+    prove_solved_witness(circuit_bytecode, witness_stack, &verification_key, &settings)
 }
 
 /// Generate an Ultra Honk proof using Keccak as the oracle hash function.
@@ -56,7 +68,7 @@ pub fn prove_ultra_honk(
 ///
 /// # Arguments
 ///
-/// * `circuit_bytecode` - The base64-encoded circuit bytecode
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
 /// * `initial_witness` - The initial witness to use for the proof
 /// * `verification_key` - The verification key (pass empty vec for first-time proving)
 /// * `disable_zk` - Whether to disable zero-knowledge (set true for public-input-only circuits)
@@ -65,25 +77,68 @@ pub fn prove_ultra_honk(
 ///
 /// # Returns
 /// * The proof as a flat byte vector
-pub fn prove_ultra_honk_keccak(
-    circuit_bytecode: &str,
+pub fn prove_ultra_honk_keccak<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
     initial_witness: WitnessMap<FieldElement>,
     verification_key: Vec<u8>,
     disable_zk: bool,
     low_memory_mode: bool,
     max_storage_usage: Option<u64>,
 ) -> Result<Vec<u8>, String> {
-    configure_memory(low_memory_mode, max_storage_usage);
     let witness_stack = execute(circuit_bytecode, initial_witness)?;
-    let serialized_solved_witness = serialize_witness(witness_stack)?;
-    let acir_buffer_uncompressed = get_acir_buffer_uncompressed(circuit_bytecode)?;
+    prove_ultra_honk_keccak_with_witness_stack(
+        circuit_bytecode,
+        witness_stack,
+        verification_key,
+        disable_zk,
+        low_memory_mode,
+        max_storage_usage,
+    )
+}
 
+/// Generate an Ultra Honk proof using Keccak as the oracle hash function
+/// from an already solved witness stack.
+/// This is suitable for on-chain (EVM/Solidity) verification.
+///
+/// # Arguments
+///
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * `witness_stack` - The solved witness stack
+/// * `verification_key` - The verification key (pass empty vec for first-time proving)
+/// * `disable_zk` - Whether to disable zero-knowledge (set true for public-input-only circuits)
+/// * `low_memory_mode` - Whether to use file-backed memory for polynomials (slower but uses less RAM)
+/// * `max_storage_usage` - Optional storage budget in bytes for file-backed memory
+///
+/// # Returns
+/// * The proof as a flat byte vector
+pub fn prove_ultra_honk_keccak_with_witness_stack<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    witness_stack: WitnessStack<FieldElement>,
+    verification_key: Vec<u8>,
+    disable_zk: bool,
+    low_memory_mode: bool,
+    max_storage_usage: Option<u64>,
+) -> Result<Vec<u8>, String> {
+    configure_memory(low_memory_mode, max_storage_usage);
     let settings = settings_ultra_honk_keccak(disable_zk);
+    prove_solved_witness(circuit_bytecode, witness_stack, &verification_key, &settings)
+}
+
+/// Prove the circuit from its solved witness stack with the given proof system settings.
+fn prove_solved_witness<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    witness_stack: WitnessStack<FieldElement>,
+    verification_key: &[u8],
+    settings: &ProofSystemSettings,
+) -> Result<Vec<u8>, String> {
+    let serialized_solved_witness = serialize_witness(witness_stack)?;
+    let acir_buffer_uncompressed = circuit_bytecode.to_acir_buffer_uncompressed()?;
+
     let response = api::circuit_prove(
         &acir_buffer_uncompressed,
         &serialized_solved_witness,
-        &verification_key,
-        &settings,
+        verification_key,
+        settings,
     )?;
 
     // Encode as: [num_public_inputs (4 bytes BE)] [public_inputs] [proof]
@@ -3,6 +3,7 @@ pub mod netsrs;
 use serde::{Deserialize, Serialize};
 
 use crate::backends::barretenberg::api;
+use crate::circuit::CircuitBytecode;
 use crate::backends::barretenberg::utils::{get_circuit_size, compute_subgroup_size};
 
 // G2 is a small fixed group, so we can hardcode it here
@@ -58,7 +59,7 @@ pub fn setup_srs(circuit_size: u32, srs_path: Option<&str>) -> Result<u32, Strin
     Ok(srs.num_points)
 }
 
-pub fn setup_srs_from_bytecode(circuit_bytecode: &str, srs_path: Option<&str>, recursive: bool) -> Result<u32, String> {
+pub fn setup_srs_from_bytecode<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C, srs_path: Option<&str>, recursive: bool) -> Result<u32, String> {
     let circuit_size = get_circuit_size(circuit_bytecode, recursive);
     setup_srs(circuit_size, srs_path)
 }
@@ -4,21 +4,20 @@ use crate::backends::barretenberg::{
     srs::{setup_srs_from_bytecode, setup_srs},
     verify::{
         verify_ultra_honk, verify_ultra_honk_keccak,
-        get_ultra_honk_verification_key, get_ultra_honk_keccak_verification_key,
+        get_ultra_honk_verification_key, get_ultra_honk_keccak_verification_key, get_public_inputs_from_proof,
     },
-    prove::{prove_ultra_honk, prove_ultra_honk_keccak},
-    utils::compute_subgroup_size,
+    prove::{prove_ultra_honk, prove_ultra_honk_keccak, prove_ultra_honk_with_witness_stack},
+    utils::{compute_subgroup_size, get_ultra_honk_fingerprint, get_ultra_honk_keccak_fingerprint},
 };
-use crate::{witness, circuit};
+use crate::{abi, witness, circuit, execute};
+use crate::test_utils::{compiled_circuit, TempPath};
 
 #[test]
 fn test_circuit_stats() {
-    // Read the JSON manifest of the circuit
-    let product_circuit_txt = std::fs::read_to_string("circuits/target/product.json").unwrap();
-    let product_circuit: serde_json::Value = serde_json::from_str(&product_circuit_txt).unwrap();
-    let product_circuit_bytecode = product_circuit["bytecode"].as_str().unwrap();
+    // Read the compiled circuit
+    let product_circuit = compiled_circuit("product");
 
-    let (_, constraint_system_buf) = circuit::decode_circuit(product_circuit_bytecode).unwrap();
+    let (_, constraint_system_buf) = circuit::decode_circuit(&product_circuit.bytecode).unwrap();
     let settings = settings_ultra_honk_poseidon2();
     let info = api::circuit_stats(&constraint_system_buf, &settings).unwrap();
     assert_eq!(info.num_gates, 36);
@@ -29,10 +28,8 @@ fn test_circuit_stats() {
 fn test_prove_and_verify_ultra_honk() {
     let _ = tracing_subscriber::fmt::try_init();
 
-    // Read the JSON manifest of the circuit
-    let product_circuit_txt = std::fs::read_to_string("circuits/target/product.json").unwrap();
-    let product_circuit: serde_json::Value = serde_json::from_str(&product_circuit_txt).unwrap();
-    let product_circuit_bytecode = product_circuit["bytecode"].as_str().unwrap();
+    // Read the compiled circuit
+    let product_circuit = compiled_circuit("product");
 
     // Setup SRS
     setup_srs(512, None).unwrap();
@@ -41,9 +38,9 @@ fn test_prove_and_verify_ultra_honk() {
     let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
 
     let start = std::time::Instant::now();
-    let vk = get_ultra_honk_verification_key(product_circuit_bytecode, false, None).unwrap();
+    let vk = get_ultra_honk_verification_key(&product_circuit, false, None).unwrap();
 
-    let proof = prove_ultra_honk(product_circuit_bytecode, initial_witness, vk.clone(), false, None).unwrap();
+    let proof = prove_ultra_honk(&product_circuit, initial_witness, vk.clone(), false, None).unwrap();
     info!("ultra honk proof generation time: {:?}", start.elapsed());
 
     let verdict = verify_ultra_honk(proof, vk).unwrap();
@@ -51,25 +48,87 @@ fn test_prove_and_verify_ultra_honk() {
     assert!(verdict);
 }
 
+#[test]
+fn test_prove_ultra_honk_prepared_circuit() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // Decode the compiled circuit once
+    let product_circuit = compiled_circuit("product").prepare().unwrap();
+
+    // Setup SRS
+    setup_srs_from_bytecode(&product_circuit, None, false).unwrap();
+
+    let vk = get_ultra_honk_verification_key(&product_circuit, false, None).unwrap();
+
+    // The same prepared circuit can be proven repeatedly
+    for (a, b) in [(5_u128, 6_u128), (3_u128, 7_u128)] {
+        let initial_witness = witness::from_vec_to_witness_map(vec![a, b, a * b]).unwrap();
+        let proof = prove_ultra_honk(&product_circuit, initial_witness, vk.clone(), false, None).unwrap();
+        assert!(verify_ultra_honk(proof, vk.clone()).unwrap());
+    }
+}
+
+#[test]
+fn test_prove_ultra_honk_from_witness_file() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // Read the compiled circuit
+    let product_circuit = compiled_circuit("product");
+
+    // Setup SRS
+    setup_srs(512, None).unwrap();
+
+    // Execute the circuit and save the witness as `nargo execute` would
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let witness_stack = execute::execute(&product_circuit, initial_witness).unwrap();
+    let witness_path = TempPath::new("product_prove_witness.gz");
+    witness::save_witness(&witness_stack, &witness_path).unwrap();
+
+    // Prove from the witness file
+    let witness_stack = witness::load_witness(&witness_path).unwrap();
+    let vk = get_ultra_honk_verification_key(&product_circuit, false, None).unwrap();
+    let proof = prove_ultra_honk_with_witness_stack(&product_circuit, witness_stack, vk.clone(), false, None).unwrap();
+
+    let verdict = verify_ultra_honk(proof, vk).unwrap();
+    assert!(verdict);
+}
+
+#[test]
+fn test_get_public_inputs_from_proof() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // Read the compiled circuit
+    let product_circuit = compiled_circuit("product");
+
+    // Setup SRS
+    setup_srs(512, None).unwrap();
+
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let vk = get_ultra_honk_verification_key(&product_circuit, false, None).unwrap();
+    let proof = prove_ultra_honk(&product_circuit, initial_witness, vk, false, None).unwrap();
+
+    let public_inputs = get_public_inputs_from_proof(&proof, &product_circuit.abi).unwrap();
+    assert_eq!(public_inputs.len(), 1);
+    assert_eq!(public_inputs["result"], abi::InputValue::Field(acvm::FieldElement::from(30_u128)));
+}
+
 #[test]
 fn test_ultra_honk_keccak() {
     let _ = tracing_subscriber::fmt::try_init();
 
-    // Read the JSON manifest of the circuit
-    let keccak_circuit_txt = std::fs::read_to_string("circuits/target/keccak.json").unwrap();
-    let keccak_circuit: serde_json::Value = serde_json::from_str(&keccak_circuit_txt).unwrap();
-    let keccak_circuit_bytecode = keccak_circuit["bytecode"].as_str().unwrap();
+    // Read the compiled circuit
+    let keccak_circuit = compiled_circuit("keccak");
 
     // Setup SRS
-    setup_srs_from_bytecode(keccak_circuit_bytecode, None, false).unwrap();
+    setup_srs_from_bytecode(&keccak_circuit, None, false).unwrap();
 
     // Get the witness map from the vector of field elements
     let initial_witness = witness::from_vec_to_witness_map(vec![2_u128, 5_u128, 10_u128, 15_u128, 20_u128]).unwrap();
 
     let start = std::time::Instant::now();
-    let vk = get_ultra_honk_keccak_verification_key(keccak_circuit_bytecode, false, false, None).unwrap();
+    let vk = get_ultra_honk_keccak_verification_key(&keccak_circuit, false, false, None).unwrap();
 
-    let proof = prove_ultra_honk_keccak(keccak_circuit_bytecode, initial_witness, vk.clone(), false, false, None).unwrap();
+    let proof = prove_ultra_honk_keccak(&keccak_circuit, initial_witness, vk.clone(), false, false, None).unwrap();
     info!("ultra honk keccak proof generation time: {:?}", start.elapsed());
 
     let verdict = verify_ultra_honk_keccak(proof, vk, false).unwrap();
@@ -82,22 +141,20 @@ fn test_ultra_honk_keccak() {
 fn test_ultra_honk_low_memory() {
     let _ = tracing_subscriber::fmt::try_init();
 
-    // Read the JSON manifest of the circuit
-    let circuit_txt = std::fs::read_to_string("circuits/target/keccak_large.json").unwrap();
-    let circuit: serde_json::Value = serde_json::from_str(&circuit_txt).unwrap();
-    let circuit_bytecode = circuit["bytecode"].as_str().unwrap();
+    // Read the compiled circuit
+    let circuit = compiled_circuit("keccak_large");
 
     // Setup SRS
-    setup_srs_from_bytecode(circuit_bytecode, None, false).unwrap();
+    setup_srs_from_bytecode(&circuit, None, false).unwrap();
 
     // Get the witness map from the vector of field elements
     let initial_witness = witness::from_vec_to_witness_map(vec![2_u128, 5_u128, 10_u128, 15_u128, 20_u128]).unwrap();
 
     let start = std::time::Instant::now();
-    let vk = get_ultra_honk_verification_key(circuit_bytecode, true, None).unwrap();
+    let vk = get_ultra_honk_verification_key(&circuit, true, None).unwrap();
 
     // Low memory mode with a limit of 5GB of storage use (falls back to RAM for the rest)
-    let proof = prove_ultra_honk(circuit_bytecode, initial_witness, vk.clone(), true, Some(5 * 1024 * 1024 * 1024)).unwrap();
+    let proof = prove_ultra_honk(&circuit, initial_witness, vk.clone(), true, Some(5 * 1024 * 1024 * 1024)).unwrap();
     info!("ultra honk low memory proof generation time: {:?}", start.elapsed());
 
     let verdict = verify_ultra_honk(proof, vk).unwrap();
@@ -108,13 +165,11 @@ fn test_ultra_honk_low_memory() {
 #[test]
 fn test_srs_setup_from_bytecode() {
     let _ = tracing_subscriber::fmt::try_init();
-    // Read the JSON manifest of the circuit
-    let product_circuit_txt = std::fs::read_to_string("circuits/target/product.json").unwrap();
-    let product_circuit: serde_json::Value = serde_json::from_str(&product_circuit_txt).unwrap();
-    let product_circuit_bytecode = product_circuit["bytecode"].as_str().unwrap();
+    // Read the compiled circuit
+    let product_circuit = compiled_circuit("product");
 
     let start = std::time::Instant::now();
-    let srs = setup_srs_from_bytecode(product_circuit_bytecode, None, false).unwrap();
+    let srs = setup_srs_from_bytecode(&product_circuit, None, false).unwrap();
     info!("srs setup time: {:?}", start.elapsed());
     // 2^6 + 1 = 65
     assert_eq!(srs, 65);
@@ -161,3 +216,17 @@ fn test_compute_subgroup_size() {
     subgroup_size = compute_subgroup_size(1000000);
     assert_eq!(subgroup_size, 1048576);
 }
+
+#[test]
+fn test_ultra_honk_fingerprint() {
+    let product_circuit = compiled_circuit("product");
+
+    let fingerprint = get_ultra_honk_fingerprint(&product_circuit).unwrap();
+    assert_eq!(fingerprint, get_ultra_honk_fingerprint(&product_circuit.prepare().unwrap()).unwrap());
+    assert_ne!(fingerprint, circuit::get_fingerprint(&product_circuit).unwrap());
+    assert_ne!(fingerprint, get_ultra_honk_keccak_fingerprint(&product_circuit, false).unwrap());
+    assert_ne!(
+        get_ultra_honk_keccak_fingerprint(&product_circuit, false).unwrap(),
+        get_ultra_honk_keccak_fingerprint(&product_circuit, true).unwrap()
+    );
+}
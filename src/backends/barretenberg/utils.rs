@@ -1,5 +1,5 @@
-use crate::backends::barretenberg::api::{self, settings_ultra_honk_poseidon2};
-use crate::circuit::decode_circuit;
+use crate::backends::barretenberg::api::{self, settings_ultra_honk_keccak, settings_ultra_honk_poseidon2};
+use crate::circuit::{get_fingerprint_with_settings, CircuitBytecode};
 
 /// Compute the next power of two that is >= `circuit_size`.
 pub fn compute_subgroup_size(circuit_size: u32) -> u32 {
@@ -8,8 +8,8 @@ pub fn compute_subgroup_size(circuit_size: u32) -> u32 {
 }
 
 /// Get the total gate count (circuit size) for the given bytecode.
-pub fn get_circuit_size(circuit_bytecode: &str, _recursion: bool) -> u32 {
-    let (_, acir_buffer_uncompressed) = if let Ok(decoded) = decode_circuit(circuit_bytecode) {
+pub fn get_circuit_size<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C, _recursion: bool) -> u32 {
+    let acir_buffer_uncompressed = if let Ok(decoded) = circuit_bytecode.to_acir_buffer_uncompressed() {
         decoded
     } else {
         return 0;
@@ -24,8 +24,8 @@ pub fn get_circuit_size(circuit_bytecode: &str, _recursion: bool) -> u32 {
 }
 
 /// Get the dyadic (next power-of-two) circuit size for the given bytecode.
-pub fn get_circuit_size_dyadic(circuit_bytecode: &str) -> u32 {
-    let (_, acir_buffer_uncompressed) = if let Ok(decoded) = decode_circuit(circuit_bytecode) {
+pub fn get_circuit_size_dyadic<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C) -> u32 {
+    let acir_buffer_uncompressed = if let Ok(decoded) = circuit_bytecode.to_acir_buffer_uncompressed() {
         decoded
     } else {
         return 0;
@@ -40,7 +40,20 @@ pub fn get_circuit_size_dyadic(circuit_bytecode: &str) -> u32 {
 }
 
 /// Get the subgroup size (next power of two >= circuit size) for the given bytecode.
-pub fn get_subgroup_size(circuit_bytecode: &str, recursion: bool) -> u32 {
+pub fn get_subgroup_size<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C, recursion: bool) -> u32 {
     let circuit_size = get_circuit_size(circuit_bytecode, recursion);
     compute_subgroup_size(circuit_size)
 }
+
+/// Get the fingerprint of the circuit combined with the settings used by `prove_ultra_honk`.
+pub fn get_ultra_honk_fingerprint<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C) -> Result<String, String> {
+    get_fingerprint_with_settings(circuit_bytecode, &settings_ultra_honk_poseidon2())
+}
+
+/// Get the fingerprint of the circuit combined with the settings used by `prove_ultra_honk_keccak`.
+pub fn get_ultra_honk_keccak_fingerprint<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    disable_zk: bool,
+) -> Result<String, String> {
+    get_fingerprint_with_settings(circuit_bytecode, &settings_ultra_honk_keccak(disable_zk))
+}
@@ -1,8 +1,14 @@
+use acvm::{
+    acir::{native_types::{Witness, WitnessMap}, FieldElement},
+    AcirField,
+};
+
+use crate::abi::{Abi, InputMap, MAIN_RETURN_NAME};
 use crate::backends::barretenberg::api::{
     self, configure_memory, proof_bytes_to_fields, settings_ultra_honk_poseidon2, settings_ultra_honk_keccak,
     FIELD_ELEMENT_SIZE,
 };
-use crate::circuit::decode_circuit;
+use crate::circuit::CircuitBytecode;
 
 /// Split a flat proof byte vector into public_inputs and proof fields.
 ///
@@ -27,26 +33,68 @@ fn split_proof(proof: &[u8]) -> Result<(Vec<Vec<u8>>, Vec<Vec<u8>>), String> {
     Ok((public_inputs, proof_fields))
 }
 
+/// Extract the public inputs embedded in a proof and decode them using the circuit ABI.
+///
+/// Works for proofs generated by both `prove_ultra_honk` and `prove_ultra_honk_keccak`.
+/// The public inputs appear in the proof in witness order: the public parameters of
+/// `main` first, then its return value. Any trailing public inputs added by the backend
+/// (e.g. pairing points) are ignored.
+///
+/// # Arguments
+///
+/// * `proof` - The flat proof bytes (prefixed with 4-byte BE num_public_inputs)
+/// * `abi` - The ABI of the circuit
+///
+/// # Returns
+/// * The public inputs keyed by parameter name, with the return value under `return`
+pub fn get_public_inputs_from_proof(proof: &[u8], abi: &Abi) -> Result<InputMap, String> {
+    let (public_inputs, _) = split_proof(proof)?;
+    let public_abi = abi.clone().public_abi();
+
+    let return_field_count = public_abi.return_type.as_ref().map_or(0, |return_type| return_type.abi_type.field_count());
+    let expected = (public_abi.field_count() + return_field_count) as usize;
+    if public_inputs.len() < expected {
+        return Err(format!(
+            "Proof has {} public inputs but the ABI expects at least {}",
+            public_inputs.len(), expected
+        ));
+    }
+
+    // The public ABI lays out its values sequentially from witness 0
+    let mut witness_map = WitnessMap::new();
+    for (i, field) in public_inputs.iter().take(expected).enumerate() {
+        witness_map.insert(Witness(i as u32), FieldElement::from_be_bytes_reduce(field));
+    }
+    let (mut input_map, return_value) = public_abi
+        .decode(&witness_map)
+        .map_err(|e| format!("Failed to decode public inputs: {}", e))?;
+    if let Some(return_value) = return_value {
+        input_map.insert(MAIN_RETURN_NAME.to_string(), return_value);
+    }
+    Ok(input_map)
+}
+
 /// Compute the Ultra Honk verification key for the given circuit.
 ///
 /// Uses poseidon2 as the oracle hash function (matching `prove_ultra_honk`).
 ///
 /// # Arguments
 ///
-/// * `circuit_bytecode` - The base64-encoded circuit bytecode
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
 /// * `low_memory_mode` - Whether to use file-backed memory for polynomials (slower but uses less RAM)
 /// * `max_storage_usage` - Optional storage budget in bytes for file-backed memory
 ///
 /// # Returns
 /// * The serialized verification key bytes
-pub fn get_ultra_honk_verification_key(
-    circuit_bytecode: &str,
+pub fn get_ultra_honk_verification_key<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
     low_memory_mode: bool,
     max_storage_usage: Option<u64>,
 ) -> Result<Vec<u8>, String> {  
     configure_memory(low_memory_mode, max_storage_usage);
-    let (_, acir_buffer_uncompressed) =
-        decode_circuit(circuit_bytecode).map_err(|e| format!("Failed to decode circuit: {}", e))?;
+    let acir_buffer_uncompressed = circuit_bytecode
+        .to_acir_buffer_uncompressed()
+        .map_err(|e| format!("Failed to decode circuit: {}", e))?;
 
     let settings = settings_ultra_honk_poseidon2();
     let vk_response = api::circuit_compute_vk(&acir_buffer_uncompressed, &settings)?;
@@ -77,22 +125,23 @@ pub fn verify_ultra_honk(proof: Vec<u8>, verification_key: Vec<u8>) -> Result<bo
 ///
 /// # Arguments
 ///
-/// * `circuit_bytecode` - The base64-encoded circuit bytecode
+/// * `circuit_bytecode` - The base64-encoded circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
 /// * `disable_zk` - Whether ZK is disabled (must match the proving setting)
 /// * `low_memory_mode` - Whether to use file-backed memory for polynomials (slower but uses less RAM)
 /// * `max_storage_usage` - Optional storage budget in bytes for file-backed memory
 ///
 /// # Returns
 /// * The serialized verification key bytes
-pub fn get_ultra_honk_keccak_verification_key(
-    circuit_bytecode: &str,
+pub fn get_ultra_honk_keccak_verification_key<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
     disable_zk: bool,
     low_memory_mode: bool,
     max_storage_usage: Option<u64>,
 ) -> Result<Vec<u8>, String> {
     configure_memory(low_memory_mode, max_storage_usage);
-    let (_, acir_buffer_uncompressed) =
-        decode_circuit(circuit_bytecode).map_err(|e| format!("Failed to decode circuit: {}", e))?;
+    let acir_buffer_uncompressed = circuit_bytecode
+        .to_acir_buffer_uncompressed()
+        .map_err(|e| format!("Failed to decode circuit: {}", e))?;
 
     let settings = settings_ultra_honk_keccak(disable_zk);
     let vk_response = api::circuit_compute_vk(&acir_buffer_uncompressed, &settings)?;
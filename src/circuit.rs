@@ -1,8 +1,361 @@
-use std::io::Read;
+use std::{borrow::Cow, collections::BTreeMap, fs, io::Read, path::Path};
 
-use acvm::acir::{circuit::Program, FieldElement};
+use acvm::acir::{
+    circuit::{Circuit, Opcode, Program},
+    FieldElement,
+};
 use base64::engine::{general_purpose, Engine};
 use flate2::bufread::GzDecoder;
+use noirc_errors::debug_info::ProgramDebugInfo;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::abi::Abi;
+use crate::debug::{decode_debug_symbols, FileMap};
+use crate::serialization::{
+    compress, decompress, deserialize_any_format, is_compressed, serialize_with_format, SerializationFormat,
+};
+
+/// The version of Noir noir_rs is built against. Circuits must be compiled
+/// with the same version of nargo to be executed and proven.
+pub const NOIR_VERSION: &str = "1.0.0-beta.20";
+
+/// The version of barretenberg-rs noir_rs proves circuits with. Programs are re-encoded
+/// in the msgpack-compact format it reads before being passed to it.
+pub const BARRETENBERG_RS_VERSION: &str = "4.2.0-aztecnr-rc.2";
+
+/// The serialization formats of ACIR programs Noir `NOIR_VERSION` can decode, identified
+/// by the marker byte of the program. Programs in the legacy bincode format have no marker.
+pub const SUPPORTED_SERIALIZATION_FORMATS: [SerializationFormat; 3] =
+    [SerializationFormat::Bincode, SerializationFormat::Msgpack, SerializationFormat::MsgpackCompact];
+
+/// A circuit compiled by `nargo compile`, as found in `target/<name>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompiledCircuit {
+    /// The version of nargo the circuit was compiled with
+    pub noir_version: String,
+    /// The hash of the circuit computed by the compiler
+    #[serde(deserialize_with = "deserialize_hash")]
+    pub hash: String,
+    /// The ABI of the main function
+    pub abi: Abi,
+    /// The base64-encoded (and gzipped) ACIR program
+    pub bytecode: String,
+    /// The compressed and base64-encoded debug symbols
+    #[serde(default)]
+    pub debug_symbols: String,
+    /// The source files referenced by the debug symbols
+    #[serde(default)]
+    pub file_map: FileMap,
+    /// The names of the ACIR functions, indexed by function id
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// The names of the unconstrained (Brillig) functions, indexed by function id
+    #[serde(default)]
+    pub brillig_names: Vec<String>,
+}
+
+/// The hash is written as a number by some versions of nargo and as a string by others
+fn deserialize_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(hash) => Ok(hash),
+        hash => Ok(hash.to_string()),
+    }
+}
+
+impl CompiledCircuit {
+    /// Parse a compiled circuit from the content of its JSON file
+    ///
+    /// The circuit is checked to be compatible with the version of Noir noir_rs
+    /// is built against (see `check_compatibility`).
+    ///
+    /// # Arguments
+    ///
+    /// * circuit_json: The content of the JSON file created by `nargo compile`
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_json(circuit_json: &str) -> Result<Self, String> {
+        let circuit = Self::from_json_unchecked(circuit_json)?;
+        circuit.check_compatibility()?;
+        Ok(circuit)
+    }
+
+    /// Parse a compiled circuit from the content of its JSON file, without
+    /// checking its compatibility with noir_rs
+    ///
+    /// # Arguments
+    ///
+    /// * circuit_json: The content of the JSON file created by `nargo compile`
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_json_unchecked(circuit_json: &str) -> Result<Self, String> {
+        serde_json::from_str(circuit_json).map_err(|e| format!("Failed to parse compiled circuit: {}", e))
+    }
+
+    /// Read a compiled circuit from a reader
+    ///
+    /// The circuit is checked to be compatible with the version of Noir noir_rs
+    /// is built against (see `check_compatibility`).
+    ///
+    /// # Arguments
+    ///
+    /// * reader: The reader to read the JSON artifact from
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_reader(reader: impl Read) -> Result<Self, String> {
+        let circuit = Self::from_reader_unchecked(reader)?;
+        circuit.check_compatibility()?;
+        Ok(circuit)
+    }
+
+    /// Read a compiled circuit from a reader, without checking its compatibility with noir_rs
+    ///
+    /// # Arguments
+    ///
+    /// * reader: The reader to read the JSON artifact from
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_reader_unchecked(reader: impl Read) -> Result<Self, String> {
+        serde_json::from_reader(reader).map_err(|e| format!("Failed to parse compiled circuit: {}", e))
+    }
+
+    /// Read a compiled circuit from its JSON file
+    ///
+    /// The circuit is checked to be compatible with the version of Noir noir_rs
+    /// is built against (see `check_compatibility`).
+    ///
+    /// # Arguments
+    ///
+    /// * path: The path to the JSON file created by `nargo compile` (e.g. `target/product.json`)
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let circuit = Self::from_file_unchecked(path)?;
+        circuit.check_compatibility()?;
+        Ok(circuit)
+    }
+
+    /// Read a compiled circuit from its JSON file, without checking its compatibility with noir_rs
+    ///
+    /// # Arguments
+    ///
+    /// * path: The path to the JSON file created by `nargo compile` (e.g. `target/product.json`)
+    ///
+    /// # Returns
+    ///
+    /// The compiled circuit
+    pub fn from_file_unchecked(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::from_reader_unchecked(std::io::BufReader::new(file))
+    }
+
+    /// Check that the circuit was compiled with the version of Noir noir_rs is built
+    /// against (`NOIR_VERSION`), that its bytecode can be decoded and that it can be
+    /// encoded for barretenberg-rs (`BARRETENBERG_RS_VERSION`)
+    pub fn check_compatibility(&self) -> Result<(), String> {
+        check_noir_version(&self.noir_version)?;
+        let format = check_serialization_format(&self.bytecode)?;
+        let program = Program::<FieldElement>::deserialize_program(&get_acir_buffer(&self.bytecode)?).map_err(|e| {
+            format!(
+                "Circuit bytecode serialized as {} cannot be decoded by Noir {}: {}",
+                format, NOIR_VERSION, e
+            )
+        })?;
+        serialize_acir_buffer_uncompressed(&program).map_err(|e| {
+            format!("Circuit cannot be encoded for barretenberg-rs {}: {}", BARRETENBERG_RS_VERSION, e)
+        })?;
+        Ok(())
+    }
+
+    /// Decode the ACIR program of the circuit
+    pub fn program(&self) -> Result<Program<FieldElement>, String> {
+        get_program(&self.bytecode)
+    }
+
+    /// Get statistics about the ACIR functions of the circuit, named after the artifact
+    pub fn stats(&self) -> Result<ProgramStats, String> {
+        let mut stats = get_program_stats(&self.program()?);
+        self.name_functions(&mut stats);
+        Ok(stats)
+    }
+
+    /// Decode the circuit once so that it can be executed and proven repeatedly
+    /// (see `PreparedCircuit`)
+    pub fn prepare(&self) -> Result<PreparedCircuit, String> {
+        let mut prepared = PreparedCircuit::new(self)?;
+        self.name_functions(&mut prepared.stats);
+        prepared.compiled_circuit = Some(self.clone());
+        Ok(prepared)
+    }
+
+    fn name_functions(&self, stats: &mut ProgramStats) {
+        for function in stats.functions.iter_mut() {
+            function.name = self.names.get(function.function_id as usize).cloned();
+        }
+        for function in stats.unconstrained_functions.iter_mut() {
+            function.name = self.brillig_names.get(function.function_id as usize).cloned();
+        }
+    }
+
+    /// Decode the debug symbols of the circuit, if the artifact has any
+    pub fn debug_info(&self) -> Result<Option<ProgramDebugInfo>, String> {
+        if self.debug_symbols.is_empty() {
+            return Ok(None);
+        }
+        decode_debug_symbols(&self.debug_symbols).map(Some)
+    }
+}
+
+/// A circuit that can be executed and proven: either its base64-encoded bytecode
+/// (as a `&str` or `String`), a `CompiledCircuit` or a `PreparedCircuit`
+pub trait CircuitBytecode {
+    /// Get the base64-encoded bytecode of the circuit
+    fn bytecode(&self) -> &str;
+
+    /// Get the decoded ACIR program of the circuit
+    fn to_program(&self) -> Result<Cow<'_, Program<FieldElement>>, String> {
+        get_program(self.bytecode()).map(Cow::Owned)
+    }
+
+    /// Get the uncompressed acir buffer of the circuit, as expected by the proving backend
+    fn to_acir_buffer_uncompressed(&self) -> Result<Cow<'_, [u8]>, String> {
+        get_acir_buffer_uncompressed(self.bytecode()).map(Cow::Owned)
+    }
+
+    /// Get the compiled circuit the bytecode comes from, with its ABI, function names and
+    /// debug symbols, if known (bare bytecode has none)
+    fn compiled_circuit(&self) -> Option<&CompiledCircuit> {
+        None
+    }
+}
+
+impl CircuitBytecode for str {
+    fn bytecode(&self) -> &str {
+        self
+    }
+
+    // Bare bytecode has no Noir version to check, but its serialization format is checked
+    // before it is decoded or passed to the backend
+    fn to_program(&self) -> Result<Cow<'_, Program<FieldElement>>, String> {
+        check_serialization_format(self)?;
+        get_program(self).map(Cow::Owned)
+    }
+
+    fn to_acir_buffer_uncompressed(&self) -> Result<Cow<'_, [u8]>, String> {
+        check_serialization_format(self)?;
+        get_acir_buffer_uncompressed(self).map(Cow::Owned)
+    }
+}
+
+impl CircuitBytecode for String {
+    fn bytecode(&self) -> &str {
+        self
+    }
+
+    fn to_program(&self) -> Result<Cow<'_, Program<FieldElement>>, String> {
+        self.as_str().to_program()
+    }
+
+    fn to_acir_buffer_uncompressed(&self) -> Result<Cow<'_, [u8]>, String> {
+        self.as_str().to_acir_buffer_uncompressed()
+    }
+}
+
+impl CircuitBytecode for CompiledCircuit {
+    fn bytecode(&self) -> &str {
+        &self.bytecode
+    }
+
+    fn compiled_circuit(&self) -> Option<&CompiledCircuit> {
+        Some(self)
+    }
+}
+
+/// A circuit decoded once from its bytecode, to be executed and proven repeatedly
+///
+/// The decoded program, the acir buffer passed to the proving backend and the
+/// statistics of the program are computed when the circuit is prepared and reused
+/// by every function it is passed to. A circuit prepared with `CompiledCircuit::prepare`
+/// keeps the compiled circuit it comes from.
+#[derive(Debug, Clone)]
+pub struct PreparedCircuit {
+    bytecode: String,
+    program: Program<FieldElement>,
+    acir_buffer_uncompressed: Vec<u8>,
+    stats: ProgramStats,
+    compiled_circuit: Option<CompiledCircuit>,
+}
+
+impl PreparedCircuit {
+    /// Decode a circuit
+    ///
+    /// Bare bytecode goes through the same checks as when it is executed or proven directly.
+    ///
+    /// # Arguments
+    ///
+    /// * circuit_bytecode: The circuit bytecode to decode (or a `CompiledCircuit`)
+    ///
+    /// # Returns
+    ///
+    /// The prepared circuit
+    pub fn new<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C) -> Result<Self, String> {
+        let program = circuit_bytecode.to_program()?.into_owned();
+        let acir_buffer_uncompressed = circuit_bytecode.to_acir_buffer_uncompressed()?.into_owned();
+        let stats = get_program_stats(&program);
+
+        Ok(PreparedCircuit {
+            bytecode: circuit_bytecode.bytecode().to_string(),
+            program,
+            acir_buffer_uncompressed,
+            stats,
+            compiled_circuit: None,
+        })
+    }
+
+    /// Get the decoded ACIR program
+    pub fn program(&self) -> &Program<FieldElement> {
+        &self.program
+    }
+
+    /// Get the uncompressed acir buffer passed to the proving backend
+    pub fn acir_buffer_uncompressed(&self) -> &[u8] {
+        &self.acir_buffer_uncompressed
+    }
+
+    /// Get the statistics of the program
+    pub fn stats(&self) -> &ProgramStats {
+        &self.stats
+    }
+}
+
+impl CircuitBytecode for PreparedCircuit {
+    fn bytecode(&self) -> &str {
+        &self.bytecode
+    }
+
+    fn to_program(&self) -> Result<Cow<'_, Program<FieldElement>>, String> {
+        Ok(Cow::Borrowed(&self.program))
+    }
+
+    fn to_acir_buffer_uncompressed(&self) -> Result<Cow<'_, [u8]>, String> {
+        Ok(Cow::Borrowed(&self.acir_buffer_uncompressed))
+    }
+
+    fn compiled_circuit(&self) -> Option<&CompiledCircuit> {
+        self.compiled_circuit.as_ref()
+    }
+}
 
 /// Get the acir buffer (compressed) from the circuit bytecode
 /// 
@@ -42,9 +395,9 @@ pub fn uncompress_acir_buffer(acir_buffer: Vec<u8>) -> Result<Vec<u8>, String> {
 
 /// Get the acir buffer (uncompressed) from the circuit bytecode.
 ///
-/// Round-trips through `Program` deserialization/serialization to ensure
-/// the output format matches the `NOIR_SERIALIZATION_FORMAT` env var
-/// (e.g. msgpack-compact for barretenberg compatibility).
+/// Round-trips through `Program` deserialization/serialization so that the
+/// output is always in the msgpack-compact format expected by barretenberg,
+/// whatever the format of the bytecode.
 ///
 /// # Arguments
 ///
@@ -58,8 +411,12 @@ pub fn get_acir_buffer_uncompressed(circuit_bytecode: &str) -> Result<Vec<u8>, S
     // Round-trip through Program to re-serialize in the current format
     let program: Program<FieldElement> = Program::deserialize_program(&acir_buffer)
         .map_err(|e| format!("Failed to deserialize program: {}", e))?;
-    let reserialized = Program::serialize_program(&program);
-    uncompress_acir_buffer(reserialized)
+    serialize_acir_buffer_uncompressed(&program)
+}
+
+/// Serialize a decoded program into the uncompressed acir buffer expected by the proving backend
+fn serialize_acir_buffer_uncompressed(program: &Program<FieldElement>) -> Result<Vec<u8>, String> {
+    encode_program(program, SerializationFormat::MsgpackCompact, false)
 }
 
 /// Decode the circuit bytecode into an acir buffer
@@ -90,4 +447,569 @@ pub fn decode_circuit(circuit_bytecode: &str) -> Result<(Vec<u8>, Vec<u8>), Stri
 pub fn get_program(circuit_bytecode: &str) -> Result<Program<FieldElement>, String> {
     let acir_buffer: Vec<u8> = get_acir_buffer(circuit_bytecode)?;
     Program::deserialize_program(&acir_buffer).map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Check that a circuit compiled with the given version of nargo can be used with noir_rs
+///
+/// # Arguments
+///
+/// * noir_version: The `noir_version` field of the compiled circuit (e.g. `1.0.0-beta.20+abcdef`)
+///
+/// # Returns
+///
+/// An error describing the mismatch if the version differs from `NOIR_VERSION`
+pub fn check_noir_version(noir_version: &str) -> Result<(), String> {
+    // Ignore the commit hash appended as build metadata
+    let version = noir_version.split('+').next().unwrap_or_default().trim();
+    if version != NOIR_VERSION {
+        return Err(format!(
+            "Circuit was compiled with Noir {} but noir_rs is built against Noir {}, recompile it with nargo {}",
+            if version.is_empty() { "(unknown version)" } else { version },
+            NOIR_VERSION,
+            NOIR_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Get the serialization format of the program in the circuit bytecode
+///
+/// Programs in the legacy bincode format have no marker byte and cannot be told apart
+/// from corrupted bytecode, so they are reported as an unrecognised marker.
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to inspect
+///
+/// # Returns
+///
+/// The serialization format read from the marker byte of the uncompressed program,
+/// or an error if the marker byte is not one of a known format
+pub fn get_serialization_format(circuit_bytecode: &str) -> Result<SerializationFormat, String> {
+    let acir_buffer = get_acir_buffer(circuit_bytecode)?;
+    let acir_buffer = if is_compressed(&acir_buffer) { decompress(&acir_buffer)? } else { acir_buffer };
+    let marker = *acir_buffer.first().ok_or("Empty circuit bytecode")?;
+    SerializationFormat::from_marker(marker).ok_or_else(|| unsupported_serialization_format(marker))
+}
+
+/// Check that the program in the circuit bytecode is serialized in one of the
+/// `SUPPORTED_SERIALIZATION_FORMATS`
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to inspect
+///
+/// # Returns
+///
+/// The serialization format, or an error if its marker byte is not a supported format
+pub fn check_serialization_format(circuit_bytecode: &str) -> Result<SerializationFormat, String> {
+    let format = get_serialization_format(circuit_bytecode)?;
+    if !SUPPORTED_SERIALIZATION_FORMATS.contains(&format) {
+        return Err(unsupported_serialization_format(format as u8));
+    }
+    Ok(format)
+}
+
+fn unsupported_serialization_format(marker: u8) -> String {
+    format!(
+        "Circuit bytecode has an unsupported serialization format marker ({}), Noir {} supports {}: \
+         recompile it with nargo {} or convert it with `circuit::convert_program`",
+        marker,
+        NOIR_VERSION,
+        SUPPORTED_SERIALIZATION_FORMATS.map(|format| format.to_string()).join(", "),
+        NOIR_VERSION
+    )
+}
+
+/// Statistics about an ACIR function
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionStats {
+    /// The id of the function in the program
+    pub function_id: u32,
+    /// The name of the function, if known
+    pub name: Option<String>,
+    /// The total number of opcodes
+    pub opcodes: usize,
+    /// The number of opcodes of each kind (`assert_zero`, `black_box`, `memory_init`,
+    /// `memory_op`, `brillig_call` and `call`)
+    pub opcodes_by_kind: BTreeMap<String, usize>,
+    /// The number of calls to each black box function (e.g. `keccakf1600`, `poseidon2_permutation`)
+    pub black_box_calls: BTreeMap<String, usize>,
+    /// The number of memory blocks initialized by the function
+    pub memory_blocks: usize,
+    /// The number of calls to other ACIR functions
+    pub calls: usize,
+    /// The number of calls to unconstrained functions
+    pub brillig_calls: usize,
+    /// The number of witnesses used by the function
+    pub witnesses: u32,
+    /// The number of private parameter witnesses
+    pub private_parameters: usize,
+    /// The number of public parameter witnesses
+    pub public_parameters: usize,
+    /// The number of return value witnesses
+    pub return_values: usize,
+}
+
+/// Statistics about an unconstrained (Brillig) function
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UnconstrainedFunctionStats {
+    /// The id of the function in the program
+    pub function_id: u32,
+    /// The name of the function, if known
+    pub name: Option<String>,
+    /// The number of Brillig opcodes of the function
+    pub bytecode_size: usize,
+}
+
+/// Statistics about an ACIR program, computed without any proving backend
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProgramStats {
+    pub functions: Vec<FunctionStats>,
+    pub unconstrained_functions: Vec<UnconstrainedFunctionStats>,
+}
+
+impl ProgramStats {
+    /// Get the total number of opcodes of the ACIR functions
+    pub fn total_opcodes(&self) -> usize {
+        self.functions.iter().map(|function| function.opcodes).sum()
+    }
+
+    /// Get the total number of calls to each black box function across the ACIR functions
+    pub fn total_black_box_calls(&self) -> BTreeMap<String, usize> {
+        let mut total = BTreeMap::new();
+        for (name, count) in self.functions.iter().flat_map(|function| &function.black_box_calls) {
+            *total.entry(name.clone()).or_insert(0) += count;
+        }
+        total
+    }
+
+    /// Get the statistics as JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+/// Get statistics about an ACIR program
+///
+/// # Arguments
+///
+/// * program: The program to inspect (see `get_program`)
+///
+/// # Returns
+///
+/// The statistics of each ACIR and unconstrained function of the program
+pub fn get_program_stats(program: &Program<FieldElement>) -> ProgramStats {
+    ProgramStats {
+        functions: program
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(function_id, function)| get_function_stats(function_id as u32, function))
+            .collect(),
+        unconstrained_functions: program
+            .unconstrained_functions
+            .iter()
+            .enumerate()
+            .map(|(function_id, function)| UnconstrainedFunctionStats {
+                function_id: function_id as u32,
+                name: None,
+                bytecode_size: function.bytecode.len(),
+            })
+            .collect(),
+    }
+}
+
+fn get_function_stats(function_id: u32, function: &Circuit<FieldElement>) -> FunctionStats {
+    let mut stats = FunctionStats {
+        function_id,
+        opcodes: function.opcodes.len(),
+        witnesses: function.current_witness_index + 1,
+        private_parameters: function.private_parameters.len(),
+        public_parameters: function.public_parameters.0.len(),
+        return_values: function.return_values.0.len(),
+        ..Default::default()
+    };
+    for opcode in &function.opcodes {
+        match opcode {
+            Opcode::BlackBoxFuncCall(call) => *stats.black_box_calls.entry(call.name().to_string()).or_insert(0) += 1,
+            Opcode::MemoryInit { .. } => stats.memory_blocks += 1,
+            Opcode::BrilligCall { .. } => stats.brillig_calls += 1,
+            Opcode::Call { .. } => stats.calls += 1,
+            Opcode::AssertZero(_) | Opcode::MemoryOp { .. } => {}
+        }
+        *stats.opcodes_by_kind.entry(opcode_kind(opcode).to_string()).or_insert(0) += 1;
+    }
+    stats
+}
+
+/// The kind of an opcode, as used in `FunctionStats::opcodes_by_kind`
+pub(crate) fn opcode_kind(opcode: &Opcode<FieldElement>) -> &'static str {
+    match opcode {
+        Opcode::AssertZero(_) => "assert_zero",
+        Opcode::BlackBoxFuncCall(_) => "black_box",
+        Opcode::MemoryInit { .. } => "memory_init",
+        Opcode::MemoryOp { .. } => "memory_op",
+        Opcode::BrilligCall { .. } => "brillig_call",
+        Opcode::Call { .. } => "call",
+    }
+}
+
+/// Encode a program in the given format, optionally gzip-compressed
+///
+/// # Arguments
+///
+/// * program: The program to encode
+/// * format: The serialization format to use
+/// * compressed: Whether to gzip the serialized program
+///
+/// # Returns
+///
+/// The encoded program (binary, use `base64` to get circuit bytecode)
+pub fn encode_program(
+    program: &Program<FieldElement>,
+    format: SerializationFormat,
+    compressed: bool,
+) -> Result<Vec<u8>, String> {
+    let buf = serialize_with_format(program, format)?;
+    if compressed {
+        compress(&buf)
+    } else {
+        Ok(buf)
+    }
+}
+
+/// Decode a program, detecting its encoding
+///
+/// The input can be binary or base64-encoded, gzipped or raw, in any of the
+/// supported serialization formats. A JSON artifact created by `nargo compile`
+/// is also accepted, in which case its bytecode is decoded.
+///
+/// # Arguments
+///
+/// * buf: The encoded program
+///
+/// # Returns
+///
+/// The program and the format it was serialized with
+pub fn decode_program(buf: &[u8]) -> Result<(Program<FieldElement>, SerializationFormat), String> {
+    let text = std::str::from_utf8(buf).map(str::trim).unwrap_or_default();
+    if text.starts_with('{') {
+        let circuit = CompiledCircuit::from_json_unchecked(text)?;
+        return decode_program(circuit.bytecode.as_bytes());
+    }
+    if !text.is_empty() && !is_compressed(buf) && text.bytes().all(|b| b.is_ascii_alphanumeric() || b"+/=".contains(&b)) {
+        return decode_program(&get_acir_buffer(text)?);
+    }
+    deserialize_any_format(buf).map_err(|e| format!("Failed to decode program: {}", e))
+}
+
+/// Convert a program to another encoding
+///
+/// # Arguments
+///
+/// * buf: The encoded program, in any encoding understood by `decode_program`
+/// * format: The serialization format to convert to, one of `SUPPORTED_SERIALIZATION_FORMATS`
+/// * compressed: Whether to gzip the serialized program
+/// * base64: Whether to base64-encode the result, as in the `bytecode` field of compiled circuits
+///
+/// # Returns
+///
+/// The converted program
+pub fn convert_program(buf: &[u8], format: SerializationFormat, compressed: bool, base64: bool) -> Result<Vec<u8>, String> {
+    let (program, _) = decode_program(buf)?;
+    encode_converted_program(&program, format, compressed, base64)
+}
+
+/// Convert a program file to another encoding
+///
+/// # Arguments
+///
+/// * input_path: The path to the program (e.g. `target/product.json` or a binary `.acir` file)
+/// * output_path: The path to write the converted program to
+/// * format: The serialization format to convert to (`bincode`, `msgpack` or `msgpack-compact`)
+/// * compressed: Whether to gzip the serialized program
+/// * base64: Whether to base64-encode the result
+///
+/// # Returns
+///
+/// The serialization format of the input program
+pub fn convert_program_file(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    format: &str,
+    compressed: bool,
+    base64: bool,
+) -> Result<SerializationFormat, String> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    let format: SerializationFormat = format.parse()?;
+    let buf = fs::read(input_path).map_err(|e| format!("Failed to read {}: {}", input_path.display(), e))?;
+    let (program, input_format) = decode_program(&buf)?;
+    let converted = encode_converted_program(&program, format, compressed, base64)?;
+    fs::write(output_path, converted).map_err(|e| format!("Failed to write {}: {}", output_path.display(), e))?;
+    Ok(input_format)
+}
+
+fn encode_converted_program(
+    program: &Program<FieldElement>,
+    format: SerializationFormat,
+    compressed: bool,
+    base64: bool,
+) -> Result<Vec<u8>, String> {
+    // Only convert to formats the converted program can be loaded back from
+    if !SUPPORTED_SERIALIZATION_FORMATS.contains(&format) {
+        return Err(format!(
+            "Cannot convert a program to {}, Noir {} supports {}",
+            format,
+            NOIR_VERSION,
+            SUPPORTED_SERIALIZATION_FORMATS.map(|format| format.to_string()).join(", ")
+        ));
+    }
+    let encoded = encode_program(program, format, compressed)?;
+    if base64 {
+        Ok(general_purpose::STANDARD.encode(encoded).into_bytes())
+    } else {
+        Ok(encoded)
+    }
+}
+
+/// Compute a stable fingerprint of a circuit
+///
+/// The fingerprint is the SHA-256 hash of the decoded program, so it does not depend
+/// on the serialization format, compression or encoding of the bytecode, nor on the
+/// debug symbols of the artifact.
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
+///
+/// # Returns
+///
+/// The fingerprint, as a hexadecimal string
+pub fn get_fingerprint<C: CircuitBytecode + ?Sized>(circuit_bytecode: &C) -> Result<String, String> {
+    let program = circuit_bytecode.to_program()?;
+    let buf = serialize_with_format(&*program, SerializationFormat::MsgpackCompact)?;
+    Ok(hex::encode(Sha256::digest(buf)))
+}
+
+/// Compute a stable fingerprint of a circuit combined with the settings of the proof system
+/// it is proven with, e.g. to key a registry of verification keys
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * settings: The settings of the proof system
+///
+/// # Returns
+///
+/// The fingerprint, as a hexadecimal string
+pub fn get_fingerprint_with_settings<C: CircuitBytecode + ?Sized, S: Serialize>(
+    circuit_bytecode: &C,
+    settings: &S,
+) -> Result<String, String> {
+    let fingerprint = get_fingerprint(circuit_bytecode)?;
+    let settings = serde_json::to_vec(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(fingerprint.as_bytes());
+    hasher.update(settings);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::test_utils::{artifact_json, artifact_path, compiled_circuit, TempPath};
+    use crate::{circuit::{self, CompiledCircuit}, execute, serialization::SerializationFormat, witness};
+
+    #[test]
+    fn test_compiled_circuit() {
+        let product_circuit = compiled_circuit("product");
+        assert!(product_circuit.noir_version.starts_with("1.0.0-beta.20"));
+        assert_eq!(product_circuit.abi.parameters.len(), 3);
+        assert_eq!(product_circuit.names, vec!["main".to_string()]);
+        assert_eq!(product_circuit.program().unwrap().functions.len(), 1);
+
+        let product_circuit_txt = std::fs::read_to_string(artifact_path("product")).unwrap();
+        let from_json = CompiledCircuit::from_json(&product_circuit_txt).unwrap();
+        assert_eq!(from_json.bytecode, product_circuit.bytecode);
+        assert_eq!(from_json.hash, product_circuit.hash);
+    }
+
+    #[test]
+    fn test_compiled_circuit_version_mismatch() {
+        let mut artifact = artifact_json("product");
+        artifact["noir_version"] = json!("1.0.0-beta.3+0000000000000000000000000000000000000000");
+        let artifact = artifact.to_string();
+
+        let err = CompiledCircuit::from_json(&artifact).unwrap_err();
+        assert!(err.contains("compiled with Noir 1.0.0-beta.3"), "{}", err);
+        assert!(err.contains(circuit::NOIR_VERSION), "{}", err);
+
+        // The check can be skipped
+        let product_circuit = CompiledCircuit::from_json_unchecked(&artifact).unwrap();
+        assert!(circuit::check_noir_version(&product_circuit.noir_version).is_err());
+        assert!(circuit::get_serialization_format(&product_circuit.bytecode).is_ok());
+    }
+
+    #[test]
+    fn test_versions_match_manifest() {
+        let manifest: toml::Value = toml::from_str(include_str!("../Cargo.toml")).unwrap();
+        let dependencies = manifest["dependencies"].as_table().unwrap();
+        let noir_dependencies: Vec<_> = dependencies
+            .values()
+            .filter(|dependency| dependency.get("git").and_then(|git| git.as_str()) == Some("https://github.com/noir-lang/noir.git"))
+            .collect();
+        assert!(!noir_dependencies.is_empty());
+        for dependency in noir_dependencies {
+            assert_eq!(dependency["rev"].as_str(), Some(format!("v{}", circuit::NOIR_VERSION).as_str()));
+        }
+        assert_eq!(
+            dependencies["barretenberg-rs"]["version"].as_str(),
+            Some(format!("={}", circuit::BARRETENBERG_RS_VERSION).as_str())
+        );
+    }
+
+    #[test]
+    fn test_unsupported_serialization_format() {
+        use base64::engine::{general_purpose, Engine};
+
+        let product_circuit = compiled_circuit("product");
+        assert_eq!(circuit::check_serialization_format(&product_circuit.bytecode), Ok(SerializationFormat::MsgpackCompact));
+
+        // Same program behind an unknown format marker
+        let mut acir_buffer = circuit::get_acir_buffer_uncompressed(&product_circuit.bytecode).unwrap();
+        acir_buffer[0] = 9;
+        let bytecode = general_purpose::STANDARD.encode(crate::serialization::compress(&acir_buffer).unwrap());
+
+        let err = circuit::check_serialization_format(&bytecode).unwrap_err();
+        assert!(err.contains("unsupported serialization format marker (9)"), "{}", err);
+        // The marker is not guessed to be legacy bincode
+        assert_eq!(circuit::get_serialization_format(&bytecode), Err(err));
+        assert!(circuit::PreparedCircuit::new(bytecode.as_str()).is_err());
+        assert!(circuit::PreparedCircuit::new(&bytecode).is_err());
+        let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+        let err = execute::execute(bytecode.as_str(), initial_witness).unwrap_err();
+        assert!(matches!(err, execute::ExecutionError::InvalidBytecode(_)), "{}", err);
+
+        let mut artifact = artifact_json("product");
+        artifact["bytecode"] = json!(bytecode);
+        assert!(CompiledCircuit::from_json(&artifact.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_program_stats() {
+        let product_circuit = compiled_circuit("product");
+        let stats = product_circuit.stats().unwrap();
+
+        assert_eq!(stats.functions.len(), 1);
+        let main = &stats.functions[0];
+        assert_eq!(main.name.as_deref(), Some("main"));
+        assert!(main.opcodes > 0);
+        assert_eq!(main.opcodes, main.opcodes_by_kind.values().sum::<usize>());
+        assert_eq!(main.calls, 0);
+        // a and b are private, result is public
+        assert_eq!(main.private_parameters, 2);
+        assert_eq!(main.public_parameters, 1);
+        assert_eq!(stats.total_opcodes(), main.opcodes);
+        assert!(main.black_box_calls.is_empty());
+
+        let keccak_circuit = compiled_circuit("keccak");
+        let stats = keccak_circuit.stats().unwrap();
+        assert!(stats.total_black_box_calls().get("keccakf1600").copied().unwrap_or_default() > 0);
+        assert_eq!(stats.to_json()["functions"][0]["name"], json!("main"));
+    }
+
+    #[test]
+    fn test_prepared_circuit() {
+        let product_circuit = compiled_circuit("product");
+        let prepared = product_circuit.prepare().unwrap();
+
+        assert_eq!(prepared.program(), &product_circuit.program().unwrap());
+        assert_eq!(prepared.stats(), &product_circuit.stats().unwrap());
+        assert_eq!(
+            prepared.acir_buffer_uncompressed(),
+            circuit::get_acir_buffer_uncompressed(&product_circuit.bytecode).unwrap().as_slice()
+        );
+
+        for (a, b) in [(5_u128, 6_u128), (3_u128, 7_u128)] {
+            let initial_witness = witness::from_vec_to_witness_map(vec![a, b, a * b]).unwrap();
+            assert!(execute::execute(&prepared, initial_witness).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_convert_program() {
+        let product_circuit = compiled_circuit("product");
+        let program = product_circuit.program().unwrap();
+
+        for format in [SerializationFormat::Bincode, SerializationFormat::Msgpack, SerializationFormat::MsgpackCompact] {
+            for (compressed, base64) in [(false, false), (true, false), (true, true), (false, true)] {
+                let converted = circuit::convert_program(product_circuit.bytecode.as_bytes(), format, compressed, base64).unwrap();
+                let (decoded, detected_format) = circuit::decode_program(&converted).unwrap();
+                assert_eq!(decoded, program);
+                assert_eq!(detected_format, format);
+            }
+        }
+
+        // Compiled circuits are accepted as input
+        let artifact = std::fs::read(artifact_path("product")).unwrap();
+        assert_eq!(circuit::decode_program(&artifact).unwrap().0, program);
+
+        // The buffer passed to the backend is always msgpack-compact
+        let acir_buffer = circuit::get_acir_buffer_uncompressed(&product_circuit.bytecode).unwrap();
+        assert_eq!(acir_buffer[0], SerializationFormat::MsgpackCompact as u8);
+
+        // Legacy bincode cannot be loaded back, so it is not a conversion target
+        let bytecode = product_circuit.bytecode.as_bytes();
+        assert!(circuit::convert_program(bytecode, SerializationFormat::BincodeLegacy, true, true).is_err());
+
+        let output = TempPath::new("product.acir");
+        circuit::convert_program_file(artifact_path("product"), &output, "msgpack", true, false).unwrap();
+        let (decoded, format) = circuit::decode_program(&std::fs::read(&output).unwrap()).unwrap();
+        assert_eq!(decoded, program);
+        assert_eq!(format, SerializationFormat::Msgpack);
+    }
+
+    #[test]
+    fn test_converted_program_can_be_loaded() {
+        use crate::circuit::CircuitBytecode;
+
+        let product_circuit = compiled_circuit("product");
+        let program = product_circuit.program().unwrap();
+
+        for format in circuit::SUPPORTED_SERIALIZATION_FORMATS {
+            let converted = circuit::convert_program(product_circuit.bytecode.as_bytes(), format, true, true).unwrap();
+            let bytecode = String::from_utf8(converted).unwrap();
+            assert_eq!(circuit::check_serialization_format(&bytecode), Ok(format));
+            assert_eq!(bytecode.to_program().unwrap().into_owned(), program);
+            assert_eq!(circuit::PreparedCircuit::new(&bytecode).unwrap().program(), &program);
+
+            let mut artifact = artifact_json("product");
+            artifact["bytecode"] = json!(bytecode);
+            assert_eq!(CompiledCircuit::from_json(&artifact.to_string()).unwrap().program().unwrap(), program);
+        }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let product_circuit = compiled_circuit("product");
+        let fingerprint = circuit::get_fingerprint(&product_circuit).unwrap();
+        assert_eq!(fingerprint.len(), 64);
+
+        // The fingerprint does not depend on how the program is encoded
+        for format in [SerializationFormat::Bincode, SerializationFormat::Msgpack] {
+            let converted = circuit::convert_program(product_circuit.bytecode.as_bytes(), format, false, true).unwrap();
+            let bytecode = String::from_utf8(converted).unwrap();
+            assert_ne!(bytecode, product_circuit.bytecode);
+            assert_eq!(circuit::get_fingerprint(&bytecode).unwrap(), fingerprint);
+        }
+
+        let keccak_circuit = compiled_circuit("keccak");
+        assert_ne!(circuit::get_fingerprint(&keccak_circuit).unwrap(), fingerprint);
+
+        let with_settings = circuit::get_fingerprint_with_settings(&product_circuit, &json!({ "hash": "poseidon2" })).unwrap();
+        assert_ne!(with_settings, fingerprint);
+    }
+}
@@ -0,0 +1,420 @@
+//! Generation of typed Rust bindings for compiled circuits, meant to be used from a build script.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//!     let artifacts = noir_rs::codegen::write_bindings(&["circuits/target/product.json"], out_dir.join("circuits.rs"), true).unwrap();
+//!     for artifact in artifacts {
+//!         println!("cargo:rerun-if-changed={}", artifact.display());
+//!     }
+//! }
+//!
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/circuits.rs"));
+//!
+//! let inputs = product::Inputs { a: FieldElement::from(5_u128), b: FieldElement::from(6_u128), result: FieldElement::from(30_u128) };
+//! let (witness_stack, outputs) = product::execute(&inputs)?;
+//! ```
+//!
+//! Each circuit gets a module named after its artifact, with an `Inputs` struct holding the
+//! parameters of `main`, a `PublicOutputs` struct holding its public parameters and return
+//! value (named `return_value`, or `return_value_` if a public parameter is already named so),
+//! and `execute` (plus `prove`, `verify` and `verification_key` when generated for
+//! barretenberg) functions. The artifact is embedded in the binary, so any change of the ABI
+//! of the circuit surfaces as a compile error in the code using the bindings.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::abi::{is_bounded_vec, AbiType, AbiVisibility, Sign};
+use crate::circuit::CompiledCircuit;
+
+/// The names imported or defined by the generated modules, that the generated structs must not shadow
+const RESERVED_NAMES: &[&str] = &[
+    "Inputs", "PublicOutputs", "FieldElement", "WitnessStack", "CompiledCircuit", "PreparedCircuit", "Map", "Value",
+    "get_noir_member", "FromNoirValue", "NoirInputs", "ToNoirValue", "String", "Vec", "Option", "Result",
+];
+
+/// The keywords that cannot be used as raw identifiers either
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do",
+    "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Generate the bindings of a compiled circuit
+///
+/// The circuit is loaded (and so validated) with `CompiledCircuit::from_file`.
+///
+/// # Arguments
+///
+/// * artifact_path: The path to the JSON file created by `nargo compile` (e.g. `target/product.json`)
+/// * barretenberg: Whether to generate the `prove`, `verify` and `verification_key` functions
+///   (the `barretenberg` feature of noir_rs must be enabled in the crate using the bindings)
+///
+/// # Returns
+///
+/// The Rust source code of the module of the circuit
+pub fn generate_bindings(artifact_path: impl AsRef<Path>, barretenberg: bool) -> Result<String, String> {
+    let artifact_path = artifact_path.as_ref();
+    let circuit = CompiledCircuit::from_file(artifact_path)?;
+    let module_name = module_name(artifact_path)?;
+    let artifact_path = fs::canonicalize(artifact_path)
+        .map_err(|e| format!("Failed to resolve {}: {}", artifact_path.display(), e))?;
+
+    let mut generator = Generator::default();
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    for param in &circuit.abi.parameters {
+        let rust_type = generator.rust_type(&param.typ)?;
+        inputs.push((param.name.clone(), rust_type.clone()));
+        if param.visibility == AbiVisibility::Public {
+            outputs.push((param.name.clone(), rust_type));
+        }
+    }
+    let return_type = match &circuit.abi.return_type {
+        Some(return_type) => Some(generator.rust_type(&return_type.abi_type)?),
+        None => None,
+    };
+
+    let mut code = String::new();
+    writeln!(code, "/// Bindings of the `{}` circuit, generated by noir_rs", module_name).unwrap();
+    writeln!(code, "#[allow(dead_code, clippy::all)]").unwrap();
+    writeln!(code, "pub mod {} {{", module_name).unwrap();
+    writeln!(code, "    use ::noir_rs::acir::{{native_types::WitnessStack, FieldElement}};").unwrap();
+    writeln!(code, "    use ::noir_rs::circuit::{{CompiledCircuit, PreparedCircuit}};").unwrap();
+    writeln!(code, "    use ::noir_rs::inputs::{{serde_json::{{Map, Value}}, get_noir_member, FromNoirValue, NoirInputs, ToNoirValue}};").unwrap();
+    writeln!(code).unwrap();
+    writeln!(code, "    /// The JSON artifact of the circuit, embedded at build time").unwrap();
+    writeln!(code, "    pub const ARTIFACT: &str = include_str!({:?});", artifact_path.display().to_string()).unwrap();
+    writeln!(code).unwrap();
+    writeln!(code, "    /// The compiled circuit").unwrap();
+    writeln!(code, "    pub fn circuit() -> &'static CompiledCircuit {{").unwrap();
+    writeln!(code, "        static CIRCUIT: std::sync::OnceLock<CompiledCircuit> = std::sync::OnceLock::new();").unwrap();
+    writeln!(code, "        CIRCUIT.get_or_init(|| CompiledCircuit::from_json(ARTIFACT).expect(\"the circuit is validated at build time\"))").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+    writeln!(code, "    /// The circuit, decoded once for repeated execution and proving").unwrap();
+    writeln!(code, "    pub fn prepared_circuit() -> &'static PreparedCircuit {{").unwrap();
+    writeln!(code, "        static PREPARED: std::sync::OnceLock<PreparedCircuit> = std::sync::OnceLock::new();").unwrap();
+    writeln!(code, "        PREPARED.get_or_init(|| circuit().prepare().expect(\"the circuit is validated at build time\"))").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+
+    for struct_code in &generator.structs {
+        code.push_str(struct_code);
+    }
+
+    writeln!(code, "    /// The inputs of the circuit").unwrap();
+    write_struct(&mut code, "Inputs", &inputs);
+    writeln!(code, "    impl NoirInputs for Inputs {{").unwrap();
+    writeln!(code, "        fn to_noir_inputs(&self) -> Value {{").unwrap();
+    write_to_noir_value_body(&mut code, &inputs);
+    writeln!(code, "        }}").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+
+    // The return value gets a field of its own, renamed if a public parameter has the same name
+    let mut return_field = "return_value".to_string();
+    while outputs.iter().any(|(name, _)| rust_identifier(name) == return_field) {
+        return_field.push('_');
+    }
+    let mut output_fields = outputs;
+    if let Some(return_type) = &return_type {
+        output_fields.push((return_field.clone(), return_type.clone()));
+    }
+    writeln!(code, "    /// The public inputs and return value of the circuit").unwrap();
+    write_struct(&mut code, "PublicOutputs", &output_fields);
+    writeln!(code, "    impl PublicOutputs {{").unwrap();
+    writeln!(code, "        /// Decode the public outputs from the public inputs keyed by parameter name,").unwrap();
+    writeln!(code, "        /// with the return value under `return`").unwrap();
+    writeln!(code, "        pub fn from_input_map(input_map: &::noir_rs::abi::InputMap) -> Result<Self, String> {{").unwrap();
+    writeln!(code, "            let abi = &circuit().abi;").unwrap();
+    writeln!(code, "            let mut values = Map::new();").unwrap();
+    writeln!(code, "            for param in abi.parameters.iter().filter(|param| param.visibility == ::noir_rs::abi::AbiVisibility::Public) {{").unwrap();
+    writeln!(code, "                let value = input_map.get(&param.name).ok_or_else(|| format!(\"missing public input `{{}}`\", param.name))?;").unwrap();
+    writeln!(code, "                values.insert(param.name.clone(), ::noir_rs::abi::input_value_to_json(value, &param.typ));").unwrap();
+    writeln!(code, "            }}").unwrap();
+    writeln!(code, "            if let (Some(value), Some(return_type)) = (input_map.get(::noir_rs::abi::MAIN_RETURN_NAME), &abi.return_type) {{").unwrap();
+    writeln!(code, "                values.insert({:?}.to_string(), ::noir_rs::abi::input_value_to_json(value, &return_type.abi_type));", return_field).unwrap();
+    writeln!(code, "            }}").unwrap();
+    writeln!(code, "            Self::from_noir_value(&Value::Object(values))").unwrap();
+    writeln!(code, "        }}").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+    write_from_noir_value(&mut code, "PublicOutputs", &output_fields);
+
+    writeln!(code, "    /// Execute the circuit").unwrap();
+    writeln!(code, "    pub fn execute(inputs: &Inputs) -> Result<(WitnessStack<FieldElement>, PublicOutputs), String> {{").unwrap();
+    writeln!(code, "        let abi = &circuit().abi;").unwrap();
+    writeln!(code, "        let result = ::noir_rs::execute::execute_with_abi(prepared_circuit(), abi, inputs.to_witness_map(abi)?)?;").unwrap();
+    writeln!(code, "        let mut input_map = result.public_inputs.clone();").unwrap();
+    writeln!(code, "        if let Some(return_value) = &result.return_value {{").unwrap();
+    writeln!(code, "            input_map.insert(::noir_rs::abi::MAIN_RETURN_NAME.to_string(), return_value.clone());").unwrap();
+    writeln!(code, "        }}").unwrap();
+    writeln!(code, "        let outputs = PublicOutputs::from_input_map(&input_map)?;").unwrap();
+    writeln!(code, "        Ok((result.witness_stack, outputs))").unwrap();
+    writeln!(code, "    }}").unwrap();
+
+    if barretenberg {
+        writeln!(code).unwrap();
+        writeln!(code, "    /// Compute the Ultra Honk verification key of the circuit").unwrap();
+        writeln!(code, "    pub fn verification_key() -> Result<Vec<u8>, String> {{").unwrap();
+        writeln!(code, "        ::noir_rs::barretenberg::verify::get_ultra_honk_verification_key(prepared_circuit(), false, None)").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "    /// Execute the circuit and generate an Ultra Honk proof").unwrap();
+        writeln!(code, "    pub fn prove(inputs: &Inputs, verification_key: Vec<u8>) -> Result<Vec<u8>, String> {{").unwrap();
+        writeln!(code, "        let initial_witness = inputs.to_witness_map(&circuit().abi)?;").unwrap();
+        writeln!(code, "        ::noir_rs::barretenberg::prove::prove_ultra_honk(prepared_circuit(), initial_witness, verification_key, false, None)").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "    /// Verify an Ultra Honk proof of the circuit and decode its public outputs").unwrap();
+        writeln!(code, "    pub fn verify(proof: Vec<u8>, verification_key: Vec<u8>) -> Result<Option<PublicOutputs>, String> {{").unwrap();
+        writeln!(code, "        let input_map = ::noir_rs::barretenberg::verify::get_public_inputs_from_proof(&proof, &circuit().abi)?;").unwrap();
+        writeln!(code, "        if !::noir_rs::barretenberg::verify::verify_ultra_honk(proof, verification_key)? {{").unwrap();
+        writeln!(code, "            return Ok(None);").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(code, "        PublicOutputs::from_input_map(&input_map).map(Some)").unwrap();
+        writeln!(code, "    }}").unwrap();
+    }
+    writeln!(code, "}}").unwrap();
+
+    Ok(code)
+}
+
+/// Generate the bindings of several compiled circuits into a single file, to be included
+/// with `include!` (see the module documentation)
+///
+/// Each circuit gets a module named after the file name of its artifact, so the artifacts
+/// must have different file names.
+///
+/// # Arguments
+///
+/// * artifact_paths: The paths to the JSON files created by `nargo compile`
+/// * output_path: The path of the file to write, usually in `OUT_DIR`
+/// * barretenberg: Whether to generate the `prove`, `verify` and `verification_key` functions
+///
+/// # Returns
+///
+/// The paths of the artifacts the bindings were generated from, for a build script
+/// to print as `cargo:rerun-if-changed` directives
+pub fn write_bindings(
+    artifact_paths: &[impl AsRef<Path>],
+    output_path: impl AsRef<Path>,
+    barretenberg: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let output_path = output_path.as_ref();
+    let mut module_names: Vec<(String, &Path)> = Vec::new();
+    for artifact_path in artifact_paths.iter().map(AsRef::as_ref) {
+        let module_name = module_name(artifact_path)?;
+        if let Some((_, other_path)) = module_names.iter().find(|(name, _)| *name == module_name) {
+            return Err(format!(
+                "{} and {} would both generate a module named `{}`",
+                other_path.display(),
+                artifact_path.display(),
+                module_name
+            ));
+        }
+        module_names.push((module_name, artifact_path));
+    }
+
+    let mut code = String::new();
+    for artifact_path in artifact_paths {
+        code.push_str(&generate_bindings(artifact_path, barretenberg)?);
+        code.push('\n');
+    }
+    fs::write(output_path, code).map_err(|e| format!("Failed to write {}: {}", output_path.display(), e))?;
+    Ok(artifact_paths.iter().map(|path| path.as_ref().to_path_buf()).collect())
+}
+
+/// Get the name of the module generated for an artifact, after its file name
+fn module_name(artifact_path: &Path) -> Result<String, String> {
+    artifact_path
+        .file_stem()
+        .map(|stem| rust_identifier(&stem.to_string_lossy().to_lowercase()))
+        .ok_or_else(|| format!("Invalid artifact path: {}", artifact_path.display()))
+}
+
+/// The Rust types generated for the structs of an ABI
+#[derive(Default)]
+struct Generator {
+    /// The struct types already generated, with their Rust name
+    struct_types: Vec<(AbiType, String)>,
+    /// The code of the generated structs
+    structs: Vec<String>,
+}
+
+impl Generator {
+    /// Get the Rust type of an ABI type, generating the structs it uses
+    fn rust_type(&mut self, typ: &AbiType) -> Result<String, String> {
+        Ok(match typ {
+            AbiType::Field => "FieldElement".to_string(),
+            AbiType::Boolean => "bool".to_string(),
+            AbiType::String { .. } => "String".to_string(),
+            AbiType::Integer { sign, width } => {
+                let bits = [8, 16, 32, 64, 128]
+                    .into_iter()
+                    .find(|bits| width <= bits)
+                    .ok_or_else(|| format!("Unsupported integer width: {}", width))?;
+                match sign {
+                    Sign::Unsigned => format!("u{}", bits),
+                    Sign::Signed => format!("i{}", bits),
+                }
+            }
+            AbiType::Array { length, typ } => format!("[{}; {}]", self.rust_type(typ)?, length),
+            AbiType::Tuple { fields } => {
+                if fields.is_empty() || fields.len() > 4 {
+                    return Err(format!("Unsupported tuple of {} elements", fields.len()));
+                }
+                let fields = fields.iter().map(|field| self.rust_type(field)).collect::<Result<Vec<_>, _>>()?;
+                format!("({},)", fields.join(", "))
+            }
+            AbiType::Struct { path, fields } if is_bounded_vec(path) => {
+                let storage = fields.iter().find(|(name, _)| name == "storage").map(|(_, typ)| typ);
+                match storage {
+                    Some(AbiType::Array { typ, .. }) => format!("Vec<{}>", self.rust_type(typ)?),
+                    _ => return Err(format!("Unexpected layout for {}", path)),
+                }
+            }
+            AbiType::Struct { path, fields } => {
+                if let Some((_, name)) = self.struct_types.iter().find(|(struct_type, _)| struct_type == typ) {
+                    return Ok(name.clone());
+                }
+                let base_name = rust_identifier(path.rsplit("::").next().unwrap_or(path));
+                let mut name = base_name.clone();
+                let mut suffix = 1;
+                while RESERVED_NAMES.contains(&name.as_str())
+                    || self.struct_types.iter().any(|(_, existing)| *existing == name)
+                {
+                    suffix += 1;
+                    name = format!("{}{}", base_name, suffix);
+                }
+                self.struct_types.push((typ.clone(), name.clone()));
+
+                let fields = fields
+                    .iter()
+                    .map(|(field_name, field_type)| Ok((field_name.clone(), self.rust_type(field_type)?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                let mut code = String::new();
+                writeln!(code, "    /// `{}`", path).unwrap();
+                write_struct(&mut code, &name, &fields);
+                writeln!(code, "    impl ToNoirValue for {} {{", name).unwrap();
+                writeln!(code, "        fn to_noir_value(&self) -> Value {{").unwrap();
+                write_to_noir_value_body(&mut code, &fields);
+                writeln!(code, "        }}").unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code).unwrap();
+                write_from_noir_value(&mut code, &name, &fields);
+                self.structs.push(code);
+                name
+            }
+        })
+    }
+}
+
+fn write_struct(code: &mut String, name: &str, fields: &[(String, String)]) {
+    writeln!(code, "    #[derive(Debug, Clone, PartialEq)]").unwrap();
+    writeln!(code, "    pub struct {} {{", name).unwrap();
+    for (field_name, rust_type) in fields {
+        writeln!(code, "        pub {}: {},", rust_identifier(field_name), rust_type).unwrap();
+    }
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+}
+
+fn write_to_noir_value_body(code: &mut String, fields: &[(String, String)]) {
+    writeln!(code, "            let mut map = Map::new();").unwrap();
+    for (field_name, _) in fields {
+        writeln!(
+            code,
+            "            map.insert({:?}.to_string(), ToNoirValue::to_noir_value(&self.{}));",
+            field_name,
+            rust_identifier(field_name)
+        )
+        .unwrap();
+    }
+    writeln!(code, "            Value::Object(map)").unwrap();
+}
+
+fn write_from_noir_value(code: &mut String, name: &str, fields: &[(String, String)]) {
+    writeln!(code, "    impl FromNoirValue for {} {{", name).unwrap();
+    writeln!(code, "        fn from_noir_value(value: &Value) -> Result<Self, String> {{").unwrap();
+    writeln!(code, "            Ok({} {{", name).unwrap();
+    for (field_name, _) in fields {
+        writeln!(code, "                {}: get_noir_member(value, {:?})?,", rust_identifier(field_name), field_name).unwrap();
+    }
+    writeln!(code, "            }})").unwrap();
+    writeln!(code, "        }}").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code).unwrap();
+}
+
+/// Turn a Noir name into a valid Rust identifier
+pub(crate) fn rust_identifier(name: &str) -> String {
+    let mut identifier: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if PATH_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    } else if RUST_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.insert_str(0, "r#");
+    }
+    identifier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_bindings, rust_identifier, write_bindings, Generator};
+    use crate::abi::AbiType;
+    use crate::test_utils::{artifact_path, TempPath};
+
+    #[test]
+    fn test_generate_bindings() {
+        let bindings = generate_bindings(artifact_path("product"), false).unwrap();
+        assert!(bindings.contains("pub mod product {"));
+        assert!(bindings.contains("pub struct Inputs {"));
+        assert!(bindings.contains("pub a: FieldElement,"));
+        assert!(bindings.contains("pub struct PublicOutputs {\n        pub result: FieldElement,\n    }"));
+        assert!(bindings.contains("pub fn execute(inputs: &Inputs)"));
+        assert!(!bindings.contains("pub fn prove("));
+
+        let bindings = generate_bindings(artifact_path("product"), true).unwrap();
+        assert!(bindings.contains("pub fn prove(inputs: &Inputs, verification_key: Vec<u8>)"));
+
+        // Keywords that cannot be raw identifiers get a suffix instead
+        assert_eq!(rust_identifier("move"), "r#move");
+        assert_eq!(rust_identifier("self"), "self_");
+        assert_eq!(rust_identifier("Self"), "Self_");
+
+        // Compiled and executed by the noir_rs_codegen_tests crate
+        let bindings = generate_bindings(artifact_path("codegen"), false).unwrap();
+        assert!(bindings.contains("pub r#move: Move,"));
+        assert!(bindings.contains("pub return_value: FieldElement,\n        pub return_value_: FieldElement,"));
+    }
+
+    #[test]
+    fn test_generated_names_do_not_clash() {
+        // Structs named after the imports of the generated module are renamed
+        let mut generator = Generator::default();
+        for (path, expected) in [("lib::Value", "Value2"), ("lib::FieldElement", "FieldElement2"), ("other::Value", "Value3")] {
+            let typ = AbiType::Struct { path: path.to_string(), fields: vec![("x".to_string(), AbiType::Field)] };
+            assert_eq!(generator.rust_type(&typ).unwrap(), expected);
+        }
+
+        // Artifacts with the same file name in different directories would generate the same module
+        let output = TempPath::new("bindings.rs");
+        let err = write_bindings(&[artifact_path("product"), format!("./{}", artifact_path("product"))], &output, false)
+            .unwrap_err();
+        assert!(err.contains("would both generate a module named `product`"), "{}", err);
+        assert!(!output.exists());
+    }
+}
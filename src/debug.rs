@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+use acvm::acir::circuit::OpcodeLocation;
+use noirc_errors::{
+    debug_info::{DebugInfo, ProgramDebugInfo},
+    Location,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::circuit::CompiledCircuit;
+use crate::execute::{assertion_message, ExecutionError};
+
+/// A source file embedded in the `file_map` of a compiled circuit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugFile {
+    pub source: String,
+    pub path: PathBuf,
+}
+
+/// The source files of a compiled circuit, keyed by file id
+pub type FileMap = BTreeMap<usize, DebugFile>;
+
+/// A resolved location in the Noir source code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// The path of the source file
+    pub path: String,
+    /// The line number (starting at 1)
+    pub line: usize,
+    /// The column number (starting at 1)
+    pub column: usize,
+    /// The source line the location points to, trimmed
+    pub snippet: String,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
+}
+
+/// Decode the `debug_symbols` field of a compiled circuit
+///
+/// # Arguments
+///
+/// * debug_symbols: The compressed and base64-encoded debug symbols
+///
+/// # Returns
+///
+/// The debug information of each ACIR function of the program
+pub fn decode_debug_symbols(debug_symbols: &str) -> Result<ProgramDebugInfo, String> {
+    ProgramDebugInfo::deserialize_compressed_base64_json(serde_json::Value::String(debug_symbols.to_string()))
+        .map_err(|e| format!("Failed to decode debug symbols: {}", e))
+}
+
+/// Parse the `file_map` field of a compiled circuit
+///
+/// # Arguments
+///
+/// * file_map: The `file_map` value of the JSON artifact
+///
+/// # Returns
+///
+/// The source files keyed by file id
+pub fn parse_file_map(file_map: &serde_json::Value) -> Result<FileMap, String> {
+    serde_json::from_value(file_map.clone()).map_err(|e| format!("Failed to parse file map: {}", e))
+}
+
+/// Resolve a location of the debug symbols into a file, line and column
+///
+/// # Arguments
+///
+/// * location: The location to resolve
+/// * file_map: The source files of the circuit
+///
+/// # Returns
+///
+/// The source location, if its file is part of the file map
+pub fn resolve_location(location: &Location, file_map: &FileMap) -> Option<SourceLocation> {
+    let file = file_map.get(&location.file.as_usize())?;
+    let offset = (location.span.start() as usize).min(file.source.len());
+    let before = file.source.get(..offset)?;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = file.source[offset..].find('\n').map(|i| offset + i).unwrap_or(file.source.len());
+
+    Some(SourceLocation {
+        path: file.path.display().to_string(),
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        snippet: file.source[line_start..line_end].trim().to_string(),
+    })
+}
+
+/// Resolve the Noir call stack of an opcode, outermost call first
+///
+/// # Arguments
+///
+/// * debug_info: The debug information of the ACIR function the opcode belongs to
+/// * opcode_location: The location of the opcode
+/// * file_map: The source files of the circuit
+///
+/// # Returns
+///
+/// The resolved call stack (empty if the opcode has no debug information)
+pub fn opcode_call_stack(
+    debug_info: &DebugInfo,
+    opcode_location: &OpcodeLocation,
+    file_map: &FileMap,
+) -> Vec<SourceLocation> {
+    debug_info
+        .opcode_location(opcode_location)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|location| resolve_location(location, file_map))
+        .collect()
+}
+
+/// An execution failure resolved to the Noir source code of the circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMappedError {
+    /// The error reported by the ACVM
+    pub message: String,
+    /// The message of the failed assertion, decoded using the ABI
+    pub assertion_message: Option<String>,
+    /// The id of the ACIR function that failed
+    pub function_id: Option<u32>,
+    /// The Noir call stack of the failure, outermost call first
+    /// (empty if the circuit has no debug symbols)
+    pub call_stack: Vec<SourceLocation>,
+}
+
+impl SourceMappedError {
+    /// Get the innermost location of the failure
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.call_stack.last()
+    }
+}
+
+impl From<String> for SourceMappedError {
+    fn from(message: String) -> Self {
+        SourceMappedError { message, assertion_message: None, function_id: None, call_stack: Vec::new() }
+    }
+}
+
+impl fmt::Display for SourceMappedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.assertion_message {
+            Some(assertion_message) => write!(f, "{}: {}", self.message, assertion_message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        for location in self.call_stack.iter().rev() {
+            write!(f, "\n    at {} `{}`", location, location.snippet)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SourceMappedError {}
+
+/// Resolve an execution failure to the Noir source code using the debug symbols of the circuit
+///
+/// Failures inside unconstrained functions are reported at the call site of the
+/// unconstrained function.
+///
+/// # Arguments
+///
+/// * error: The error returned by the execution of the circuit
+/// * circuit: The compiled circuit that was executed
+///
+/// # Returns
+///
+/// The error with its Noir call stack and decoded assertion message
+pub fn resolve_execution_error(error: &ExecutionError, circuit: &CompiledCircuit) -> SourceMappedError {
+    let mut resolved = SourceMappedError::from(error.to_string());
+    let Some(failure) = error.failure() else {
+        return resolved;
+    };
+    resolved.assertion_message = failure
+        .assertion_payload
+        .as_ref()
+        .and_then(|payload| assertion_message(payload, &circuit.abi))
+        .or_else(|| failure.assertion_message.clone());
+    resolved.function_id = Some(failure.function_id);
+
+    let Ok(Some(debug_symbols)) = circuit.debug_info() else {
+        return resolved;
+    };
+    for frame in &failure.call_stack {
+        let Some(debug_info) = debug_symbols.debug_infos.get(frame.function_id as usize) else {
+            continue;
+        };
+        let mut call_stack = opcode_call_stack(debug_info, &frame.opcode_location, &circuit.file_map);
+        if call_stack.is_empty() {
+            if let OpcodeLocation::Brillig { acir_index, .. } = frame.opcode_location {
+                call_stack = opcode_call_stack(debug_info, &OpcodeLocation::Acir(acir_index), &circuit.file_map);
+            }
+        }
+        resolved.call_stack.extend(call_stack);
+    }
+    resolved
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::{circuit::OpcodeLocation, native_types::Witness, FieldElement};
+use acvm::pwg::ResolvedAssertionPayload;
+use noirc_abi::display_abi_error;
+
+use super::limits::ExecutionLimit;
+use crate::abi::Abi;
+
+/// A frame of the call stack of an execution failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodeFrame {
+    /// The id of the ACIR function the opcode belongs to
+    pub function_id: u32,
+    /// The location of the opcode, an `OpcodeLocation::Brillig` for the frames of unconstrained code
+    pub opcode_location: OpcodeLocation,
+}
+
+/// Where and how the execution of a circuit failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionFailure {
+    /// The error reported by the ACVM
+    pub message: String,
+    /// The id of the ACIR function that failed
+    pub function_id: u32,
+    /// The location of the failing opcode
+    pub opcode_location: OpcodeLocation,
+    /// The ACIR calls leading to the failing opcode, followed by its Brillig call stack,
+    /// outermost call first
+    pub call_stack: Vec<OpcodeFrame>,
+    /// The id of the Brillig function that failed, if the failure happened in unconstrained code
+    pub brillig_function_id: Option<u32>,
+    /// The payload of the failed assertion, if any
+    pub assertion_payload: Option<ResolvedAssertionPayload<FieldElement>>,
+    /// The message of the failed assertion, decoded from its payload. Custom error types
+    /// can only be decoded using the ABI (see `ExecutionError::decode_assertion_payload`).
+    pub assertion_message: Option<String>,
+    /// The values of the witnesses used by the failing opcode, as solved when it failed
+    pub witness_values: BTreeMap<Witness, FieldElement>,
+}
+
+/// An error occurring during the execution of a circuit
+///
+/// The `Display` implementation gives the same messages as the ACVM.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExecutionError {
+    /// The bytecode of the circuit could not be decoded
+    #[error("{0}")]
+    InvalidBytecode(String),
+    /// The inputs could not be encoded, or the outputs decoded, using the ABI
+    #[error("{0}")]
+    Abi(String),
+    /// A constraint or an assertion is not satisfied, in constrained or unconstrained code
+    #[error("{}", .0.message)]
+    AssertionFailed(Box<ExecutionFailure>),
+    /// An opcode could not be solved as some of its witnesses have no value, e.g. a missing input
+    #[error("{}", failure.message)]
+    MissingWitness {
+        /// The witnesses without a value
+        witnesses: Vec<Witness>,
+        failure: Box<ExecutionFailure>,
+    },
+    /// An unconstrained function failed without an assertion, e.g. on an out of bounds index
+    #[error("{}", .0.message)]
+    BrilligFailed(Box<ExecutionFailure>),
+    /// A foreign call (oracle) could not be answered
+    #[error("{}", failure.message)]
+    ForeignCallFailed {
+        /// The name of the foreign call
+        function: String,
+        failure: Box<ExecutionFailure>,
+    },
+    /// Any other solving error, e.g. an invalid bit size or a failed black box function
+    #[error("{}", .0.message)]
+    SolvingFailed(Box<ExecutionFailure>),
+    /// The execution was stopped by one of its limits (see `ExecuteOptions`)
+    #[error("{}", failure.message)]
+    LimitExceeded {
+        /// The limit that was reached
+        limit: ExecutionLimit,
+        failure: Box<ExecutionFailure>,
+    },
+    /// Any other error, e.g. an unknown function or invalid options
+    #[error("{0}")]
+    Other(String),
+}
+
+impl ExecutionError {
+    /// Get where and how the execution failed, if the error occurred while solving the circuit
+    pub fn failure(&self) -> Option<&ExecutionFailure> {
+        match self {
+            ExecutionError::AssertionFailed(failure)
+            | ExecutionError::BrilligFailed(failure)
+            | ExecutionError::SolvingFailed(failure)
+            | ExecutionError::MissingWitness { failure, .. }
+            | ExecutionError::ForeignCallFailed { failure, .. }
+            | ExecutionError::LimitExceeded { failure, .. } => Some(failure),
+            ExecutionError::InvalidBytecode(_) | ExecutionError::Abi(_) | ExecutionError::Other(_) => None,
+        }
+    }
+
+    fn failure_mut(&mut self) -> Option<&mut ExecutionFailure> {
+        match self {
+            ExecutionError::AssertionFailed(failure)
+            | ExecutionError::BrilligFailed(failure)
+            | ExecutionError::SolvingFailed(failure)
+            | ExecutionError::MissingWitness { failure, .. }
+            | ExecutionError::ForeignCallFailed { failure, .. }
+            | ExecutionError::LimitExceeded { failure, .. } => Some(failure),
+            ExecutionError::InvalidBytecode(_) | ExecutionError::Abi(_) | ExecutionError::Other(_) => None,
+        }
+    }
+
+    /// Decode the payload of a failed assertion using the error types of the ABI
+    ///
+    /// # Arguments
+    ///
+    /// * abi: The ABI of the circuit
+    ///
+    /// # Returns
+    ///
+    /// The error, with its assertion message set if the payload could be decoded
+    pub fn decode_assertion_payload(mut self, abi: &Abi) -> Self {
+        if let Some(failure) = self.failure_mut() {
+            if let Some(message) = failure.assertion_payload.as_ref().and_then(|payload| assertion_message(payload, abi)) {
+                failure.assertion_message = Some(message);
+            }
+        }
+        self
+    }
+}
+
+impl From<ExecutionError> for String {
+    fn from(error: ExecutionError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Decode the message of a failed assertion, as nargo would display it
+///
+/// # Arguments
+///
+/// * payload: The payload of the failed assertion
+/// * abi: The ABI of the circuit, holding the custom error types (or an empty ABI)
+///
+/// # Returns
+///
+/// The message, or `None` if the payload has an unknown error type
+pub fn assertion_message(payload: &ResolvedAssertionPayload<FieldElement>, abi: &Abi) -> Option<String> {
+    match payload {
+        ResolvedAssertionPayload::String(message) => Some(message.clone()),
+        ResolvedAssertionPayload::Raw(raw) => {
+            let error_type = abi.error_types.get(&raw.selector)?;
+            Some(display_abi_error(&raw.data, error_type.clone()).to_string())
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::time::Instant;
+
+use acvm::acir::{
+    circuit::{brillig::BrilligBytecode, Circuit, Opcode, OpcodeLocation, Program},
+    native_types::{Witness, WitnessMap, WitnessStack},
+    FieldElement,
+};
+use acvm::pwg::{
+    ACVMStatus, BrilligSolverStatus, OpcodeNotSolvable, OpcodeResolutionError, ResolvedAssertionPayload, StepResult, ACVM,
+};
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+
+use super::error::{ExecutionError, ExecutionFailure, OpcodeFrame};
+use super::limits::{ExecutionLimit, Limits};
+use super::profile::Profiler;
+use crate::foreign_calls::{ForeignCallHandler, ForeignCallResult, ForeignCallWaitInfo};
+use crate::witness::opcode_witnesses;
+
+/// Solves the functions of a program, following the ACIR calls and answering the foreign calls
+///
+/// This mirrors `execute_program` of nargo (`tooling/nargo/src/ops/execute.rs` in Noir
+/// v1.0.0-beta.20), which cannot be wrapped as it neither exposes the state of the ACVM when
+/// an opcode fails (the witness values and unsolved witnesses of an `ExecutionFailure`) nor
+/// lets the opcodes and Brillig instructions be stepped through to enforce `Limits` and record
+/// a `Profiler`. Anything else is left to the ACVM, and `test_executor_matches_nargo` checks
+/// that both solve the test circuits to the same witness stack when bumping Noir.
+pub(crate) struct ProgramExecutor<'a, E> {
+    functions: &'a [Circuit<FieldElement>],
+    unconstrained_functions: &'a [BrilligBytecode<FieldElement>],
+    blackbox_solver: &'a Bn254BlackBoxSolver,
+    foreign_call_executor: &'a mut E,
+    witness_stack: WitnessStack<FieldElement>,
+    /// The ACIR calls leading to the function being solved
+    call_stack: Vec<OpcodeFrame>,
+    profiler: Option<&'a mut Profiler>,
+    limits: Limits,
+}
+
+impl<'a, E: ForeignCallHandler> ProgramExecutor<'a, E> {
+    pub(crate) fn new(
+        program: &'a Program<FieldElement>,
+        blackbox_solver: &'a Bn254BlackBoxSolver,
+        foreign_call_executor: &'a mut E,
+    ) -> Self {
+        ProgramExecutor {
+            functions: &program.functions,
+            unconstrained_functions: &program.unconstrained_functions,
+            blackbox_solver,
+            foreign_call_executor,
+            witness_stack: WitnessStack::default(),
+            call_stack: Vec::new(),
+            profiler: None,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Stop the execution once one of the given limits is reached
+    pub(crate) fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Record the profile of the execution into the given profiler
+    pub(crate) fn with_profiler(mut self, profiler: Option<&'a mut Profiler>) -> Self {
+        self.profiler = profiler;
+        self
+    }
+
+    pub(crate) fn execute(
+        mut self,
+        initial_witness: WitnessMap<FieldElement>,
+    ) -> Result<WitnessStack<FieldElement>, ExecutionError> {
+        if self.functions.is_empty() {
+            return Err(ExecutionError::InvalidBytecode("The program has no function".to_string()));
+        }
+        let main_witness = self.execute_circuit(0, initial_witness)?;
+        self.witness_stack.push(0, main_witness);
+        Ok(self.witness_stack)
+    }
+
+    fn execute_circuit(
+        &mut self,
+        function_id: u32,
+        initial_witness: WitnessMap<FieldElement>,
+    ) -> Result<WitnessMap<FieldElement>, ExecutionError> {
+        let functions = self.functions;
+        let circuit = &functions[function_id as usize];
+        let mut acvm = ACVM::new(
+            self.blackbox_solver,
+            &circuit.opcodes,
+            initial_witness,
+            self.unconstrained_functions,
+            &circuit.assert_messages,
+        );
+
+        // With limits, unconstrained functions are stepped through here rather than by the ACVM
+        let step_brillig = self.limits.is_active();
+        if let Some(profiler) = self.profiler.as_deref_mut() {
+            profiler.enter_function(function_id);
+            acvm.with_profiler(!step_brillig);
+        }
+        if circuit.opcodes.is_empty() {
+            return Ok(acvm.finalize());
+        }
+
+        loop {
+            // Opcodes are solved one by one so that each can be profiled and the limits checked in between
+            let opcode_index = acvm.instruction_pointer();
+            if let Err(limit) = self.limits.check() {
+                return Err(self.limit_error(function_id, circuit, &acvm, limit, Vec::new()));
+            }
+            let started = self.profiler.is_some().then(Instant::now);
+            let opcode = &circuit.opcodes[opcode_index];
+            let status = match opcode {
+                Opcode::BrilligCall { id, .. } if step_brillig => {
+                    self.solve_brillig_call(function_id, circuit, &mut acvm, id.0)?
+                }
+                _ => acvm.solve_opcode(),
+            };
+            if let (Some(profiler), Some(started)) = (self.profiler.as_deref_mut(), started) {
+                let solved = acvm.instruction_pointer() != opcode_index;
+                profiler.record_opcode(function_id, opcode_index, opcode, started.elapsed(), solved);
+                for sample in acvm.take_profiling_samples() {
+                    if let Some(OpcodeLocation::Brillig { .. }) = sample.call_stack.last() {
+                        let brillig_function_id = sample.brillig_function_id.map(|id| id.0);
+                        profiler.record_brillig_instruction(function_id, brillig_function_id, sample.call_stack);
+                    }
+                }
+            }
+
+            match status {
+                ACVMStatus::Solved => break,
+                ACVMStatus::InProgress => {}
+                ACVMStatus::Failure(error) => return Err(self.solving_error(function_id, circuit, &acvm, error)),
+                ACVMStatus::RequiresForeignCall(foreign_call) => {
+                    let started = self.profiler.is_some().then(Instant::now);
+                    let result = self.execute_foreign_call(function_id, circuit, &acvm, foreign_call);
+                    if let (Some(profiler), Some(started)) = (self.profiler.as_deref_mut(), started) {
+                        // The foreign calls are part of the time spent in the unconstrained function
+                        profiler.record_opcode(function_id, opcode_index, opcode, started.elapsed(), false);
+                    }
+                    acvm.resolve_pending_foreign_call(result?);
+                }
+                ACVMStatus::RequiresAcirCall(call_info) => {
+                    let callee_id = call_info.id.0;
+                    let callee = &functions[callee_id as usize];
+                    let opcode_location = OpcodeLocation::Acir(opcode_index);
+                    self.call_stack.push(OpcodeFrame { function_id, opcode_location });
+                    let callee_witness = self.execute_circuit(callee_id, call_info.initial_witness)?;
+                    self.call_stack.pop();
+
+                    let mut return_values = Vec::new();
+                    for index in callee.return_values.indices() {
+                        let Some(value) = callee_witness.get_index(index) else {
+                            let error: OpcodeResolutionError<FieldElement> =
+                                OpcodeNotSolvable::MissingAssignment(index).into();
+                            let message = format!("Failed to solve program: '{}'", error);
+                            let failure = self.failure(function_id, circuit, &acvm, message, Vec::new());
+                            return Err(ExecutionError::MissingWitness { witnesses: vec![Witness(index)], failure });
+                        };
+                        return_values.push(*value);
+                    }
+                    acvm.resolve_pending_acir_call(return_values);
+                    self.witness_stack.push(callee_id, callee_witness);
+                }
+            }
+        }
+        Ok(acvm.finalize())
+    }
+
+    /// Execute the unconstrained function called by the current opcode one instruction at a time,
+    /// counting its steps and checking the limits while it runs
+    fn solve_brillig_call(
+        &mut self,
+        function_id: u32,
+        circuit: &Circuit<FieldElement>,
+        acvm: &mut ACVM<'a, FieldElement, Bn254BlackBoxSolver>,
+        brillig_function_id: u32,
+    ) -> Result<ACVMStatus<FieldElement>, ExecutionError> {
+        let mut solver = match acvm.step_into_brillig() {
+            StepResult::IntoBrillig(solver) => solver,
+            // The call was skipped (e.g. its predicate is false) or could not start
+            StepResult::Status(status) => return Ok(status),
+        };
+        let acir_index = acvm.instruction_pointer();
+
+        loop {
+            let location = OpcodeLocation::Brillig { acir_index, brillig_index: solver.program_counter() };
+            if let Err(limit) = self.limits.count_brillig_step() {
+                return Err(self.limit_error(function_id, circuit, acvm, limit, vec![location]));
+            }
+            if let Some(profiler) = self.profiler.as_deref_mut() {
+                profiler.record_brillig_instruction(function_id, Some(brillig_function_id), vec![location]);
+            }
+            match solver.step() {
+                Ok(BrilligSolverStatus::InProgress) => {}
+                Ok(BrilligSolverStatus::Finished) => return Ok(acvm.finish_brillig_with_solver(solver)),
+                Ok(BrilligSolverStatus::ForeignCallWait(foreign_call)) => {
+                    let result = self.execute_foreign_call(function_id, circuit, acvm, foreign_call)?;
+                    solver.resolve_pending_foreign_call(result);
+                }
+                Err(error) => return Ok(ACVMStatus::Failure(error)),
+            }
+        }
+    }
+
+    fn execute_foreign_call(
+        &mut self,
+        function_id: u32,
+        circuit: &Circuit<FieldElement>,
+        acvm: &ACVM<'_, FieldElement, Bn254BlackBoxSolver>,
+        foreign_call: ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ExecutionError> {
+        self.foreign_call_executor.execute(&foreign_call).map_err(|e| {
+            let failure = self.failure(function_id, circuit, acvm, e.to_string(), Vec::new());
+            ExecutionError::ForeignCallFailed { function: foreign_call.function, failure }
+        })
+    }
+
+    fn limit_error(
+        &self,
+        function_id: u32,
+        circuit: &Circuit<FieldElement>,
+        acvm: &ACVM<'_, FieldElement, Bn254BlackBoxSolver>,
+        limit: ExecutionLimit,
+        brillig_call_stack: Vec<OpcodeLocation>,
+    ) -> ExecutionError {
+        let failure = self.failure(function_id, circuit, acvm, limit.to_string(), brillig_call_stack);
+        ExecutionError::LimitExceeded { limit, failure }
+    }
+
+    fn solving_error(
+        &self,
+        function_id: u32,
+        circuit: &Circuit<FieldElement>,
+        acvm: &ACVM<'_, FieldElement, Bn254BlackBoxSolver>,
+        error: OpcodeResolutionError<FieldElement>,
+    ) -> ExecutionError {
+        let (payload, brillig_function_id, brillig_call_stack) = match &error {
+            OpcodeResolutionError::UnsatisfiedConstrain { payload, .. } => (payload.clone(), None, Vec::new()),
+            OpcodeResolutionError::BrilligFunctionFailed { function_id, call_stack, payload } => {
+                (payload.clone(), Some(function_id.0), call_stack.clone())
+            }
+            _ => (None, None, Vec::new()),
+        };
+        // An assertion with a message is reported as such by the ACVM, any other failure as a solving error
+        let message = match payload {
+            Some(_) => "Failed assertion".to_string(),
+            None => format!("Failed to solve program: '{}'", error),
+        };
+        let mut failure = self.failure(function_id, circuit, acvm, message, brillig_call_stack);
+        failure.brillig_function_id = brillig_function_id;
+        failure.assertion_message = match &payload {
+            Some(ResolvedAssertionPayload::String(message)) => Some(message.clone()),
+            _ => None,
+        };
+        failure.assertion_payload = payload;
+
+        match error {
+            OpcodeResolutionError::UnsatisfiedConstrain { .. } => ExecutionError::AssertionFailed(failure),
+            OpcodeResolutionError::BrilligFunctionFailed { .. } if failure.assertion_payload.is_some() => {
+                ExecutionError::AssertionFailed(failure)
+            }
+            OpcodeResolutionError::BrilligFunctionFailed { .. } => ExecutionError::BrilligFailed(failure),
+            OpcodeResolutionError::OpcodeNotSolvable(reason) => {
+                let witnesses = match reason {
+                    OpcodeNotSolvable::MissingAssignment(index) => vec![Witness(index)],
+                    _ => {
+                        let mut witnesses = circuit
+                            .opcodes
+                            .get(acvm.instruction_pointer())
+                            .map(|opcode| opcode_witnesses(opcode, true))
+                            .unwrap_or_default();
+                        witnesses.retain(|witness| !acvm.witness_map().contains_key(witness));
+                        witnesses.sort();
+                        witnesses.dedup();
+                        witnesses
+                    }
+                };
+                ExecutionError::MissingWitness { witnesses, failure }
+            }
+            _ => ExecutionError::SolvingFailed(failure),
+        }
+    }
+
+    /// Describe a failure of the opcode the ACVM stopped at
+    fn failure(
+        &self,
+        function_id: u32,
+        circuit: &Circuit<FieldElement>,
+        acvm: &ACVM<'_, FieldElement, Bn254BlackBoxSolver>,
+        message: String,
+        brillig_call_stack: Vec<OpcodeLocation>,
+    ) -> Box<ExecutionFailure> {
+        let instruction_pointer = acvm.instruction_pointer();
+        let mut call_stack = self.call_stack.clone();
+        if brillig_call_stack.is_empty() {
+            call_stack.push(OpcodeFrame { function_id, opcode_location: OpcodeLocation::Acir(instruction_pointer) });
+        } else {
+            call_stack.extend(
+                brillig_call_stack.into_iter().map(|opcode_location| OpcodeFrame { function_id, opcode_location }),
+            );
+        }
+        let opcode_location =
+            call_stack.last().map_or(OpcodeLocation::Acir(instruction_pointer), |frame| frame.opcode_location);
+
+        let solved_witness = acvm.witness_map();
+        let witness_values = circuit
+            .opcodes
+            .get(instruction_pointer)
+            .map(|opcode| opcode_witnesses(opcode, true))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|witness| solved_witness.get(&witness).map(|value| (witness, *value)))
+            .collect();
+
+        Box::new(ExecutionFailure {
+            message,
+            function_id,
+            opcode_location,
+            call_stack,
+            brillig_function_id: None,
+            assertion_payload: None,
+            assertion_message: None,
+            witness_values,
+        })
+    }
+}
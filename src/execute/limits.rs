@@ -0,0 +1,100 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// How many Brillig instructions are executed between two checks of the deadline and cancellation token
+const BRILLIG_CHECK_INTERVAL: u64 = 1024;
+
+/// A token cancelling the executions it is given to, e.g. from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the executions using this token (or any of its clones)
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The limit that stopped an execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionLimit {
+    /// The maximum number of Brillig instructions was reached
+    BrilligSteps(u64),
+    /// The deadline has passed
+    Deadline,
+    /// The cancellation token was cancelled
+    Cancelled,
+}
+
+impl fmt::Display for ExecutionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionLimit::BrilligSteps(max) => write!(f, "Execution exceeded the limit of {} Brillig steps", max),
+            ExecutionLimit::Deadline => write!(f, "Execution exceeded its deadline"),
+            ExecutionLimit::Cancelled => write!(f, "Execution was cancelled"),
+        }
+    }
+}
+
+/// The limits of an execution, and the Brillig steps counted so far
+#[derive(Default)]
+pub(crate) struct Limits {
+    max_brillig_steps: Option<u64>,
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+    brillig_steps: u64,
+}
+
+impl Limits {
+    pub(crate) fn new(
+        max_brillig_steps: Option<u64>,
+        deadline: Option<Instant>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Self {
+        Limits { max_brillig_steps, deadline, cancellation_token, brillig_steps: 0 }
+    }
+
+    /// Whether any limit is set, in which case unconstrained functions are executed step by step
+    pub(crate) fn is_active(&self) -> bool {
+        self.max_brillig_steps.is_some() || self.deadline.is_some() || self.cancellation_token.is_some()
+    }
+
+    /// Check the deadline and cancellation token
+    pub(crate) fn check(&self) -> Result<(), ExecutionLimit> {
+        if self.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(ExecutionLimit::Cancelled);
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(ExecutionLimit::Deadline);
+        }
+        Ok(())
+    }
+
+    /// Count a Brillig instruction about to be executed, checking the deadline and
+    /// cancellation token every `BRILLIG_CHECK_INTERVAL` instructions
+    pub(crate) fn count_brillig_step(&mut self) -> Result<(), ExecutionLimit> {
+        if let Some(max) = self.max_brillig_steps.filter(|max| self.brillig_steps >= *max) {
+            return Err(ExecutionLimit::BrilligSteps(max));
+        }
+        self.brillig_steps += 1;
+        if self.brillig_steps % BRILLIG_CHECK_INTERVAL == 0 {
+            self.check()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,383 @@
+use std::time::Instant;
+
+use acvm::acir::{
+    circuit::{
+        brillig::{BrilligFunctionId, BrilligInputs, BrilligOutputs},
+        Circuit, Opcode, Program,
+    },
+    native_types::{Expression, Witness, WitnessMap, WitnessStack},
+    FieldElement,
+};
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use nargo::foreign_calls::default::DefaultForeignCallBuilder;
+
+use crate::abi::{decode_public_values, input_value_to_json, parse_json_inputs, Abi, InputMap, InputValue};
+use crate::circuit::{CircuitBytecode, CompiledCircuit};
+use crate::debug::{resolve_execution_error, SourceMappedError};
+use crate::foreign_calls::{
+    FallbackForeignCallExecutor, ForeignCallHandler, JsonRpcForeignCallExecutor, NargoForeignCallExecutor,
+    OracleResolver, PrintCaptureForeignCallExecutor, PrintedOutput,
+};
+
+mod error;
+mod executor;
+mod limits;
+mod profile;
+#[cfg(test)]
+mod tests;
+
+pub use error::{assertion_message, ExecutionError, ExecutionFailure, OpcodeFrame};
+use executor::ProgramExecutor;
+pub use limits::{CancellationToken, ExecutionLimit};
+use limits::Limits;
+pub use profile::{ExecutionProfile, FunctionProfile, OpcodeProfile, ProfileMetric, UnconstrainedFunctionProfile};
+use profile::Profiler;
+
+/// The result of the execution of a circuit, with its outputs decoded using the ABI
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    /// The solved witness stack
+    pub witness_stack: WitnessStack<FieldElement>,
+    /// The public inputs of the main function, keyed by parameter name
+    pub public_inputs: InputMap,
+    /// The value returned by the main function, if any
+    pub return_value: Option<InputValue>,
+    /// The values printed by the circuit, if captured (see `ExecuteOptions::capture_output`)
+    pub output: Vec<PrintedOutput>,
+    /// The profile of the execution, if recorded (see `ExecuteOptions::profile`)
+    pub profile: Option<ExecutionProfile>,
+}
+
+impl ExecutionResult {
+    /// Get the solved witness map of the main function
+    pub fn main_witness(&self) -> Option<&WitnessMap<FieldElement>> {
+        self.witness_stack.peek().map(|item| &item.witness)
+    }
+
+    /// Get the public inputs as a JSON object
+    ///
+    /// # Arguments
+    ///
+    /// * abi: The ABI of the circuit
+    pub fn public_inputs_json(&self, abi: &Abi) -> serde_json::Value {
+        serde_json::Value::Object(
+            abi.parameters
+                .iter()
+                .filter_map(|param| {
+                    self.public_inputs.get(&param.name).map(|value| (param.name.clone(), input_value_to_json(value, &param.typ)))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the return value as JSON, if the main function returns something
+    ///
+    /// # Arguments
+    ///
+    /// * abi: The ABI of the circuit
+    pub fn return_value_json(&self, abi: &Abi) -> Option<serde_json::Value> {
+        match (&self.return_value, &abi.return_type) {
+            (Some(value), Some(return_type)) => Some(input_value_to_json(value, &return_type.abi_type)),
+            _ => None,
+        }
+    }
+}
+
+/// Execute the circuit and return the serialized solved witness stack
+///
+/// The initial witness is not checked against the parameters of the circuit
+/// (see `validation::validate_witness_map`).
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * initial_witness: The initial witness to use for the execution
+///
+/// # Returns
+///
+/// The Witness Stack
+pub fn execute<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    initial_witness: WitnessMap<FieldElement>,
+) -> Result<WitnessStack<FieldElement>, ExecutionError> {
+    let program = circuit_bytecode.to_program().map_err(ExecutionError::InvalidBytecode)?;
+    solve_program(&program, initial_witness)
+}
+
+/// The options of `execute_with_options`
+///
+/// The deadline and cancellation token are checked before each ACIR opcode and every 1024 Brillig
+/// instructions, so a single black box function call or foreign call is not interrupted.
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteOptions {
+    /// The JSON-RPC oracle resolver answering the foreign calls that are not handled
+    /// by the default executors nor by the executor given to `execute_with_options`
+    pub oracle_resolver: Option<OracleResolver>,
+    /// Capture the values printed by the circuit into `ExecutionResult::output`
+    /// instead of writing them to stdout
+    pub capture_output: bool,
+    /// Emit the values printed by the circuit as `tracing` events with the `noir_rs::print`
+    /// target instead of writing them to stdout
+    pub trace_output: bool,
+    /// The name of the circuit, used to tag the `tracing` events (defaults to `main`)
+    pub circuit_name: Option<String>,
+    /// Record the opcodes solved, Brillig instructions executed, black box calls and time spent
+    /// into `ExecutionResult::profile`, mapped to the Noir source if the circuit has debug symbols
+    pub profile: bool,
+    /// Stop the execution with `ExecutionError::LimitExceeded` once the unconstrained functions
+    /// have executed this many Brillig instructions in total
+    pub max_brillig_steps: Option<u64>,
+    /// Stop the execution with `ExecutionError::LimitExceeded` once this instant has passed
+    pub deadline: Option<Instant>,
+    /// Stop the execution with `ExecutionError::LimitExceeded` once this token is cancelled
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+/// Execute a circuit with the given options
+///
+/// The outputs are decoded, assertion messages resolved and the profile named after the
+/// functions and source of the circuit only if it comes with its compiled circuit (a
+/// `CompiledCircuit`, or a `PreparedCircuit` from `CompiledCircuit::prepare`). For bare
+/// bytecode, the outputs are left empty and can be decoded with `decode_execution_result`.
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * initial_witness: The initial witness to use for the execution
+/// * foreign_call_executor: The executor answering the foreign calls of the circuit
+///   (e.g. an empty `foreign_calls::ForeignCallRegistry`)
+/// * options: The execution options
+///
+/// # Returns
+///
+/// The execution result
+pub fn execute_with_options<C: CircuitBytecode + ?Sized, E: ForeignCallHandler>(
+    circuit_bytecode: &C,
+    initial_witness: WitnessMap<FieldElement>,
+    foreign_call_executor: &mut E,
+    options: &ExecuteOptions,
+) -> Result<ExecutionResult, ExecutionError> {
+    let program = circuit_bytecode.to_program().map_err(ExecutionError::InvalidBytecode)?;
+    let circuit = circuit_bytecode.compiled_circuit();
+    let oracle_resolver = options
+        .oracle_resolver
+        .as_ref()
+        .map(JsonRpcForeignCallExecutor::new)
+        .transpose()
+        .map_err(ExecutionError::Other)?;
+    let foreign_call_executor = FallbackForeignCallExecutor {
+        primary: NargoForeignCallExecutor(DefaultForeignCallBuilder::default().build()),
+        fallback: Some(FallbackForeignCallExecutor { primary: foreign_call_executor, fallback: oracle_resolver }),
+    };
+    // The printed values are intercepted before they reach the default print executor
+    let print_capture = PrintCaptureForeignCallExecutor {
+        circuit_name: options.circuit_name.clone().unwrap_or_else(|| "main".to_string()),
+        capture: options.capture_output,
+        trace: options.trace_output,
+        output: Vec::new(),
+    };
+    let mut foreign_call_executor = FallbackForeignCallExecutor {
+        primary: print_capture,
+        fallback: Some(foreign_call_executor),
+    };
+
+    let mut profiler = options.profile.then(Profiler::new);
+    let limits = Limits::new(options.max_brillig_steps, options.deadline, options.cancellation_token.clone());
+    let witness_stack = ProgramExecutor::new(&program, &Bn254BlackBoxSolver::default(), &mut foreign_call_executor)
+        .with_profiler(profiler.as_mut())
+        .with_limits(limits)
+        .execute(initial_witness)
+        .map_err(|e| match circuit {
+            Some(circuit) => e.decode_assertion_payload(&circuit.abi),
+            None => e,
+        })?;
+
+    let mut result = match circuit {
+        Some(circuit) => decode_execution_result(&circuit.abi, witness_stack).map_err(ExecutionError::Abi)?,
+        None => ExecutionResult {
+            witness_stack,
+            public_inputs: InputMap::new(),
+            return_value: None,
+            output: Vec::new(),
+            profile: None,
+        },
+    };
+    result.output = foreign_call_executor.primary.output;
+    result.profile = profiler.map(|profiler| {
+        let mut profile = profiler.finish();
+        if let Some(circuit) = circuit {
+            profile.annotate_with_names(&circuit.names, &circuit.brillig_names);
+            if let Ok(Some(debug_symbols)) = circuit.debug_info() {
+                profile.annotate_with_debug_symbols(&debug_symbols, &circuit.file_map);
+            }
+        }
+        profile
+    });
+    Ok(result)
+}
+
+/// Execute the circuit, answering its foreign calls (oracles) with the given executor
+///
+/// The executor can be a `foreign_calls::ForeignCallRegistry` of Rust functions or any
+/// other `foreign_calls::ForeignCallHandler` (wrap the executors of nargo in a
+/// `foreign_calls::NargoForeignCallExecutor`). It is only called for the foreign calls that
+/// are not handled by default, i.e. anything but `print` and the mocking oracles of `std::test`.
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * initial_witness: The initial witness to use for the execution
+/// * foreign_call_executor: The executor answering the foreign calls of the circuit
+///
+/// # Returns
+///
+/// The Witness Stack
+pub fn execute_with_foreign_calls<C: CircuitBytecode + ?Sized, E: ForeignCallHandler>(
+    circuit_bytecode: &C,
+    initial_witness: WitnessMap<FieldElement>,
+    foreign_call_executor: &mut E,
+) -> Result<WitnessStack<FieldElement>, ExecutionError> {
+    let program = circuit_bytecode.to_program().map_err(ExecutionError::InvalidBytecode)?;
+    let mut foreign_call_executor = FallbackForeignCallExecutor {
+        primary: NargoForeignCallExecutor(DefaultForeignCallBuilder::default().build()),
+        fallback: Some(foreign_call_executor),
+    };
+
+    solve_program_with_executor(&program, initial_witness, &mut foreign_call_executor)
+}
+
+/// Execute a compiled circuit, resolving any failure to the Noir source code
+/// using the debug symbols of the circuit
+///
+/// # Arguments
+///
+/// * circuit: The compiled circuit to execute
+/// * initial_witness: The initial witness to use for the execution
+///
+/// # Returns
+///
+/// The Witness Stack
+pub fn execute_with_debug_info(
+    circuit: &CompiledCircuit,
+    initial_witness: WitnessMap<FieldElement>,
+) -> Result<WitnessStack<FieldElement>, SourceMappedError> {
+    let program = circuit.program()?;
+    solve_program(&program, initial_witness).map_err(|e| resolve_execution_error(&e, circuit))
+}
+
+/// Call an unconstrained (Brillig) function of a compiled circuit as a Rust function
+///
+/// Only the unconstrained functions called from constrained code are compiled into their own
+/// Brillig function (listed in the `brillig_names` of the artifact). As the artifact only holds
+/// the ABI of `main`, the signature of the function must be given as an ABI whose parameters
+/// and return type match the ones of the function, in the same order.
+///
+/// # Arguments
+///
+/// * circuit: The compiled circuit containing the function
+/// * function_name: The name of the unconstrained function
+/// * signature: The ABI describing the parameters and return type of the function
+/// * arguments: A JSON object mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The value returned by the function, if any
+pub fn call_unconstrained(
+    circuit: &CompiledCircuit,
+    function_name: &str,
+    signature: &Abi,
+    arguments: &serde_json::Value,
+) -> Result<Option<InputValue>, ExecutionError> {
+    let function_id = circuit
+        .brillig_names
+        .iter()
+        .position(|name| name == function_name)
+        .ok_or_else(|| ExecutionError::Other(format!("Unknown unconstrained function: {}", function_name)))?;
+    let program = circuit.program().map_err(ExecutionError::InvalidBytecode)?;
+
+    let input_map = parse_json_inputs(signature, arguments).map_err(ExecutionError::Abi)?;
+    let calldata = signature
+        .encode(&input_map, None)
+        .map_err(|e| ExecutionError::Abi(format!("Failed to encode arguments: {}", e)))?;
+    let parameter_count = signature.field_count();
+    let return_count = signature.return_type.as_ref().map_or(0, |return_type| return_type.abi_type.field_count());
+
+    // Wrap the function in an ACIR function taking the arguments as parameters
+    // and writing the return value right after them
+    let inputs = (0..parameter_count).map(|i| BrilligInputs::Single(Expression::from(Witness(i)))).collect();
+    let outputs = (parameter_count..parameter_count + return_count).map(|i| BrilligOutputs::Simple(Witness(i))).collect();
+    let main = Circuit {
+        current_witness_index: (parameter_count + return_count).saturating_sub(1),
+        opcodes: vec![Opcode::BrilligCall { id: BrilligFunctionId(function_id as u32), inputs, outputs, predicate: None }],
+        private_parameters: (0..parameter_count).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    let wrapper = Program { functions: vec![main], unconstrained_functions: program.unconstrained_functions };
+
+    let witness_stack = solve_program(&wrapper, calldata).map_err(|e| e.decode_assertion_payload(&circuit.abi))?;
+    let solved_witness = witness_stack
+        .peek()
+        .map(|item| &item.witness)
+        .ok_or_else(|| ExecutionError::Other("The witness stack is empty".to_string()))?;
+    let (_, return_value) = signature
+        .decode(solved_witness)
+        .map_err(|e| ExecutionError::Abi(format!("Failed to decode the return value: {}", e)))?;
+    Ok(return_value)
+}
+
+fn solve_program(
+    program: &Program<FieldElement>,
+    initial_witness: WitnessMap<FieldElement>,
+) -> Result<WitnessStack<FieldElement>, ExecutionError> {
+    let mut foreign_call_executor = NargoForeignCallExecutor(DefaultForeignCallBuilder::default().build());
+    solve_program_with_executor(program, initial_witness, &mut foreign_call_executor)
+}
+
+fn solve_program_with_executor<E: ForeignCallHandler>(
+    program: &Program<FieldElement>,
+    initial_witness: WitnessMap<FieldElement>,
+    foreign_call_executor: &mut E,
+) -> Result<WitnessStack<FieldElement>, ExecutionError> {
+    let blackbox_solver = Bn254BlackBoxSolver::default();
+    ProgramExecutor::new(program, &blackbox_solver, foreign_call_executor).execute(initial_witness)
+}
+
+/// Execute the circuit and decode its public inputs and return value using the ABI
+///
+/// # Arguments
+///
+/// * circuit_bytecode: The circuit bytecode to execute (or a `CompiledCircuit` or `PreparedCircuit`)
+/// * abi: The ABI of the circuit
+/// * initial_witness: The initial witness to use for the execution
+///
+/// # Returns
+///
+/// The execution result
+pub fn execute_with_abi<C: CircuitBytecode + ?Sized>(
+    circuit_bytecode: &C,
+    abi: &Abi,
+    initial_witness: WitnessMap<FieldElement>,
+) -> Result<ExecutionResult, ExecutionError> {
+    let witness_stack = execute(circuit_bytecode, initial_witness).map_err(|e| e.decode_assertion_payload(abi))?;
+    decode_execution_result(abi, witness_stack).map_err(ExecutionError::Abi)
+}
+
+/// Decode the public inputs and return value of the main function from a solved witness stack
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * witness_stack: The solved witness stack
+///
+/// # Returns
+///
+/// The execution result
+pub fn decode_execution_result(abi: &Abi, witness_stack: WitnessStack<FieldElement>) -> Result<ExecutionResult, String> {
+    let main_witness = witness_stack
+        .peek()
+        .map(|item| &item.witness)
+        .ok_or("The witness stack is empty")?;
+    let (public_inputs, return_value) = decode_public_values(abi, main_witness)?;
+
+    Ok(ExecutionResult { witness_stack, public_inputs, return_value, output: Vec::new(), profile: None })
+}
@@ -0,0 +1,312 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use acvm::acir::{
+    circuit::{Opcode, OpcodeLocation},
+    FieldElement,
+};
+use noirc_errors::debug_info::ProgramDebugInfo;
+use serde::{Serialize, Serializer};
+
+use crate::circuit::opcode_kind;
+use crate::debug::{opcode_call_stack, FileMap, SourceLocation};
+
+/// The weight given to each stack of a collapsed-stack (flamegraph) report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileMetric {
+    /// The number of ACIR opcodes solved and Brillig instructions executed
+    Samples,
+    /// The time spent solving the ACIR opcodes, in microseconds. The time spent in an
+    /// unconstrained function, including its foreign calls, goes to its `brillig_call` opcode.
+    Time,
+}
+
+/// The profile of an ACIR function
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionProfile {
+    /// The id of the function in the program
+    pub function_id: u32,
+    /// The name of the function, if known
+    pub name: Option<String>,
+    /// The number of times the function was executed
+    pub calls: usize,
+    /// The number of opcodes solved
+    pub opcodes: usize,
+    /// The number of opcodes solved of each kind (see `circuit::FunctionStats::opcodes_by_kind`)
+    pub opcodes_by_kind: BTreeMap<String, usize>,
+    /// The number of Brillig instructions executed by the unconstrained functions it called
+    pub brillig_instructions: usize,
+    /// The time spent solving its opcodes, excluding the ACIR functions it called
+    #[serde(rename = "time_ns", serialize_with = "serialize_nanos")]
+    pub time: Duration,
+}
+
+/// The profile of an unconstrained (Brillig) function
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UnconstrainedFunctionProfile {
+    /// The id of the function in the program
+    pub function_id: u32,
+    /// The name of the function, if known
+    pub name: Option<String>,
+    /// The number of times the function was called from ACIR
+    pub calls: usize,
+    /// The number of Brillig instructions executed
+    pub instructions: usize,
+}
+
+/// The profile of an ACIR opcode or a Brillig instruction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpcodeProfile {
+    /// The id of the ACIR function the opcode belongs to
+    pub function_id: u32,
+    /// The location of the opcode. For a Brillig instruction, the Brillig call stack
+    /// leading to it, innermost last.
+    #[serde(serialize_with = "serialize_locations")]
+    pub location: Vec<OpcodeLocation>,
+    /// The kind of the opcode (see `circuit::FunctionStats::opcodes_by_kind`), or `brillig`
+    pub kind: String,
+    /// The number of times the opcode was solved or the instruction executed
+    pub count: usize,
+    /// The time spent solving the opcode (zero for Brillig instructions)
+    #[serde(rename = "time_ns", serialize_with = "serialize_nanos")]
+    pub time: Duration,
+    /// The Noir call stack of the opcode, outermost call first
+    /// (empty until the profile is annotated with debug symbols)
+    pub source: Vec<SourceLocation>,
+}
+
+/// The profile of an execution, recorded when `ExecuteOptions::profile` is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionProfile {
+    pub functions: Vec<FunctionProfile>,
+    pub unconstrained_functions: Vec<UnconstrainedFunctionProfile>,
+    /// Every opcode solved and Brillig instruction executed, by location
+    pub opcodes: Vec<OpcodeProfile>,
+    /// The number of calls to each black box function from ACIR
+    pub black_box_calls: BTreeMap<String, usize>,
+    /// The total time of the execution
+    #[serde(rename = "wall_time_ns", serialize_with = "serialize_nanos")]
+    pub wall_time: Duration,
+}
+
+impl ExecutionProfile {
+    /// Get the number of ACIR opcodes solved by all the functions
+    pub fn total_opcodes(&self) -> usize {
+        self.functions.iter().map(|function| function.opcodes).sum()
+    }
+
+    /// Get the number of Brillig instructions executed by all the functions
+    pub fn total_brillig_instructions(&self) -> usize {
+        self.functions.iter().map(|function| function.brillig_instructions).sum()
+    }
+
+    /// Name the functions after the `names` and `brillig_names` of a compiled circuit
+    ///
+    /// # Arguments
+    ///
+    /// * names: The names of the ACIR functions
+    /// * brillig_names: The names of the unconstrained functions
+    pub fn annotate_with_names(&mut self, names: &[String], brillig_names: &[String]) {
+        for function in self.functions.iter_mut() {
+            function.name = names.get(function.function_id as usize).cloned();
+        }
+        for function in self.unconstrained_functions.iter_mut() {
+            function.name = brillig_names.get(function.function_id as usize).cloned();
+        }
+    }
+
+    /// Map the opcodes to the Noir source code. Brillig instructions without debug
+    /// information are mapped to the call site of their unconstrained function.
+    ///
+    /// # Arguments
+    ///
+    /// * debug_symbols: The decoded debug symbols of the program (see `debug::decode_debug_symbols`)
+    /// * file_map: The source files of the circuit (see `debug::parse_file_map`)
+    pub fn annotate_with_debug_symbols(&mut self, debug_symbols: &ProgramDebugInfo, file_map: &FileMap) {
+        for opcode in self.opcodes.iter_mut() {
+            let Some(debug_info) = debug_symbols.debug_infos.get(opcode.function_id as usize) else {
+                continue;
+            };
+            opcode.source = opcode
+                .location
+                .iter()
+                .flat_map(|location| opcode_call_stack(debug_info, location, file_map))
+                .collect();
+            if opcode.source.is_empty() {
+                if let Some(OpcodeLocation::Brillig { acir_index, .. }) = opcode.location.first() {
+                    opcode.source = opcode_call_stack(debug_info, &OpcodeLocation::Acir(*acir_index), file_map);
+                }
+            }
+        }
+    }
+
+    /// Get the profile as JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// Get the profile in the collapsed-stack format read by flamegraph tools (e.g. `inferno-flamegraph`)
+    ///
+    /// Each stack starts with the ACIR function, followed by the Noir call stack of the opcode
+    /// (or its location, without debug symbols) and the kind of the opcode.
+    ///
+    /// # Arguments
+    ///
+    /// * metric: The weight of each stack
+    ///
+    /// # Returns
+    ///
+    /// One `frame;frame;... weight` line per stack
+    pub fn to_collapsed_stacks(&self, metric: ProfileMetric) -> String {
+        let names: BTreeMap<u32, &str> = self
+            .functions
+            .iter()
+            .filter_map(|function| Some((function.function_id, function.name.as_deref()?)))
+            .collect();
+        let mut stacks: BTreeMap<String, u128> = BTreeMap::new();
+
+        for opcode in &self.opcodes {
+            let weight = match metric {
+                ProfileMetric::Samples => opcode.count as u128,
+                ProfileMetric::Time => opcode.time.as_micros(),
+            };
+            if weight == 0 {
+                continue;
+            }
+            let mut frames = vec![match names.get(&opcode.function_id) {
+                Some(name) => frame_label(name),
+                None => format!("function {}", opcode.function_id),
+            }];
+            if opcode.source.is_empty() {
+                frames.extend(opcode.location.iter().map(|location| location.to_string()));
+            } else {
+                let labels = opcode.source.iter().map(|source| frame_label(&format!("{} {}", source, source.snippet)));
+                frames.extend(labels);
+            }
+            frames.push(opcode.kind.clone());
+            *stacks.entry(frames.join(";")).or_insert(0) += weight;
+        }
+
+        let mut collapsed = String::new();
+        for (stack, weight) in stacks {
+            writeln!(collapsed, "{} {}", stack, weight).unwrap();
+        }
+        collapsed
+    }
+}
+
+/// Records the profile of an execution as the opcodes are solved
+pub(crate) struct Profiler {
+    started: Instant,
+    functions: BTreeMap<u32, FunctionProfile>,
+    unconstrained_functions: BTreeMap<u32, UnconstrainedFunctionProfile>,
+    opcodes: BTreeMap<(u32, Vec<OpcodeLocation>), OpcodeProfile>,
+    black_box_calls: BTreeMap<String, usize>,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Profiler {
+            started: Instant::now(),
+            functions: BTreeMap::new(),
+            unconstrained_functions: BTreeMap::new(),
+            opcodes: BTreeMap::new(),
+            black_box_calls: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn enter_function(&mut self, function_id: u32) {
+        self.function(function_id).calls += 1;
+    }
+
+    /// Record the time spent on an ACIR opcode, counting it once it is solved
+    /// (an opcode waiting for a foreign call or an ACIR call is visited several times)
+    pub(crate) fn record_opcode(
+        &mut self,
+        function_id: u32,
+        opcode_index: usize,
+        opcode: &Opcode<FieldElement>,
+        elapsed: Duration,
+        solved: bool,
+    ) {
+        let kind = opcode_kind(opcode);
+        let function = self.function(function_id);
+        function.time += elapsed;
+        if solved {
+            function.opcodes += 1;
+            *function.opcodes_by_kind.entry(kind.to_string()).or_insert(0) += 1;
+        }
+        let entry = self.opcode(function_id, vec![OpcodeLocation::Acir(opcode_index)], kind);
+        entry.time += elapsed;
+        if !solved {
+            return;
+        }
+        entry.count += 1;
+        match opcode {
+            Opcode::BlackBoxFuncCall(call) => *self.black_box_calls.entry(call.name().to_string()).or_insert(0) += 1,
+            Opcode::BrilligCall { id, .. } => self.unconstrained_function(id.0).calls += 1,
+            _ => {}
+        }
+    }
+
+    /// Record the execution of a Brillig instruction
+    pub(crate) fn record_brillig_instruction(
+        &mut self,
+        function_id: u32,
+        brillig_function_id: Option<u32>,
+        call_stack: Vec<OpcodeLocation>,
+    ) {
+        self.function(function_id).brillig_instructions += 1;
+        if let Some(brillig_function_id) = brillig_function_id {
+            self.unconstrained_function(brillig_function_id).instructions += 1;
+        }
+        self.opcode(function_id, call_stack, "brillig").count += 1;
+    }
+
+    pub(crate) fn finish(self) -> ExecutionProfile {
+        ExecutionProfile {
+            functions: self.functions.into_values().collect(),
+            unconstrained_functions: self.unconstrained_functions.into_values().collect(),
+            opcodes: self.opcodes.into_values().collect(),
+            black_box_calls: self.black_box_calls,
+            wall_time: self.started.elapsed(),
+        }
+    }
+
+    fn function(&mut self, function_id: u32) -> &mut FunctionProfile {
+        self.functions.entry(function_id).or_insert_with(|| FunctionProfile { function_id, ..Default::default() })
+    }
+
+    fn unconstrained_function(&mut self, function_id: u32) -> &mut UnconstrainedFunctionProfile {
+        self.unconstrained_functions
+            .entry(function_id)
+            .or_insert_with(|| UnconstrainedFunctionProfile { function_id, ..Default::default() })
+    }
+
+    fn opcode(&mut self, function_id: u32, location: Vec<OpcodeLocation>, kind: &str) -> &mut OpcodeProfile {
+        self.opcodes.entry((function_id, location.clone())).or_insert_with(|| OpcodeProfile {
+            function_id,
+            location,
+            kind: kind.to_string(),
+            count: 0,
+            time: Duration::ZERO,
+            source: Vec::new(),
+        })
+    }
+}
+
+/// Frames are separated by `;` in the collapsed-stack format
+fn frame_label(label: &str) -> String {
+    label.trim_end_matches(';').replace(';', ",")
+}
+
+fn serialize_nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+fn serialize_locations<S: Serializer>(locations: &[OpcodeLocation], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(locations.iter().map(|location| location.to_string()))
+}
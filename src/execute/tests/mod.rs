@@ -0,0 +1,222 @@
+use acvm::acir::{native_types::{Witness, WitnessMap}, FieldElement};
+use serde_json::json;
+
+use crate::test_utils::compiled_circuit;
+use crate::{abi, execute, witness};
+
+#[test]
+fn test_execute_with_abi() {
+    let product_circuit = compiled_circuit("product");
+    let product_abi = &product_circuit.abi;
+
+    let initial_witness = witness::from_json_to_witness_map(product_abi, &json!({ "a": 5, "b": 6, "result": 30 })).unwrap();
+    let result = execute::execute_with_abi(&product_circuit, product_abi, initial_witness).unwrap();
+
+    assert_eq!(result.public_inputs.len(), 1);
+    assert_eq!(result.public_inputs["result"], abi::InputValue::Field(FieldElement::from(30_u128)));
+    assert_eq!(result.public_inputs_json(product_abi), json!({ "result": format!("0x{:0>64}", "1e") }));
+    assert!(result.return_value.is_none());
+}
+
+#[test]
+fn test_execute_with_debug_info() {
+    let product_circuit = compiled_circuit("product");
+
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    assert!(execute::execute_with_debug_info(&product_circuit, initial_witness).is_ok());
+
+    // a * b != result
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 31_u128]).unwrap();
+    let error = execute::execute_with_debug_info(&product_circuit, initial_witness).unwrap_err();
+    assert_eq!(error.function_id, Some(0));
+    let location = error.location().expect("the failure should be mapped to the source");
+    assert!(location.path.ends_with("main.nr"), "{}", location.path);
+    assert_eq!(location.line, 2);
+    assert_eq!(location.snippet, "assert(a * b == result);");
+    assert!(error.to_string().contains("main.nr:2:"), "{}", error);
+}
+
+#[test]
+fn test_execution_error() {
+    let product_circuit = compiled_circuit("product");
+
+    // a * b != result
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 31_u128]).unwrap();
+    let error = execute::execute(&product_circuit, initial_witness).unwrap_err();
+    assert!(matches!(error, execute::ExecutionError::AssertionFailed(_)), "{:?}", error);
+    let failure = error.failure().unwrap();
+    assert_eq!(failure.function_id, 0);
+    assert_eq!(failure.call_stack.last().map(|frame| frame.opcode_location), Some(failure.opcode_location));
+    assert_eq!(failure.witness_values.get(&Witness(2)), Some(&FieldElement::from(31_u128)));
+
+    // a and b are missing
+    let mut initial_witness = WitnessMap::new();
+    initial_witness.insert(Witness(2), FieldElement::from(30_u128));
+    let error = execute::execute(&product_circuit, initial_witness).unwrap_err();
+    match &error {
+        execute::ExecutionError::MissingWitness { witnesses, .. } => assert_eq!(witnesses, &[Witness(0), Witness(1)]),
+        _ => panic!("unexpected error: {:?}", error),
+    }
+
+    let error = execute::execute("not a circuit", witness::from_vec_to_witness_map(vec![1_u128]).unwrap()).unwrap_err();
+    assert!(matches!(error, execute::ExecutionError::InvalidBytecode(_)), "{:?}", error);
+    assert!(error.failure().is_none());
+}
+
+#[test]
+fn test_call_unconstrained() {
+    let circuit = compiled_circuit("unconstrained");
+    let u32_type = json!({ "kind": "integer", "sign": "unsigned", "width": 32 });
+    let signature = abi::parse_abi(&json!({
+        "parameters": [
+            { "name": "a", "type": u32_type, "visibility": "private" },
+            { "name": "b", "type": u32_type, "visibility": "private" }
+        ],
+        "return_type": {
+            "abi_type": { "kind": "tuple", "fields": [u32_type, u32_type] },
+            "visibility": "private"
+        },
+        "error_types": {}
+    }))
+    .unwrap();
+
+    let return_value =
+        execute::call_unconstrained(&circuit, "sum_and_product", &signature, &json!({ "a": 3, "b": 4 })).unwrap().unwrap();
+    let return_type = &signature.return_type.as_ref().unwrap().abi_type;
+    assert_eq!(abi::input_value_to_json(&return_value, return_type), json!([7, 12]));
+
+    let err = execute::call_unconstrained(&circuit, "unknown", &signature, &json!({ "a": 3, "b": 4 })).unwrap_err();
+    assert!(err.to_string().contains("Unknown unconstrained function"), "{}", err);
+}
+
+#[test]
+fn test_execution_profile() {
+    use crate::foreign_calls::ForeignCallRegistry;
+
+    let circuit = compiled_circuit("unconstrained");
+    let initial_witness = || witness::from_vec_to_witness_map(vec![3_u128, 4_u128, 7_u128]).unwrap();
+
+    let options = execute::ExecuteOptions { profile: true, ..Default::default() };
+    let result = execute::execute_with_options(&circuit, initial_witness(), &mut ForeignCallRegistry::new(), &options).unwrap();
+    let profile = result.profile.unwrap();
+
+    // Without ACIR calls, every opcode of main is solved once
+    assert_eq!(profile.functions.len(), 1);
+    assert_eq!(profile.functions[0].name.as_deref(), Some("main"));
+    assert_eq!(profile.functions[0].calls, 1);
+    assert_eq!(profile.total_opcodes(), circuit.stats().unwrap().total_opcodes());
+
+    let sum_and_product = profile
+        .unconstrained_functions
+        .iter()
+        .find(|function| function.name.as_deref() == Some("sum_and_product"))
+        .unwrap();
+    assert_eq!(sum_and_product.calls, 1);
+    assert!(sum_and_product.instructions > 0);
+    assert!(profile.total_brillig_instructions() >= sum_and_product.instructions);
+
+    let collapsed = profile.to_collapsed_stacks(execute::ProfileMetric::Samples);
+    let mut samples = 0;
+    for line in collapsed.lines() {
+        assert!(line.starts_with("main;"), "{}", line);
+        let (_, weight) = line.rsplit_once(' ').unwrap();
+        samples += weight.parse::<usize>().unwrap();
+    }
+    assert_eq!(samples, profile.total_opcodes() + profile.total_brillig_instructions());
+    assert!(profile.to_json()["functions"][0]["time_ns"].is_u64());
+
+    // Nothing is recorded by default
+    let result =
+        execute::execute_with_options(&circuit, initial_witness(), &mut ForeignCallRegistry::new(), &Default::default())
+            .unwrap();
+    assert!(result.profile.is_none());
+}
+
+#[test]
+fn test_execute_with_options_without_artifact() {
+    use crate::foreign_calls::ForeignCallRegistry;
+
+    let circuit = compiled_circuit("unconstrained");
+    let initial_witness = || witness::from_vec_to_witness_map(vec![3_u128, 4_u128, 7_u128]).unwrap();
+    let options = execute::ExecuteOptions { profile: true, ..Default::default() };
+    let expected = execute::execute_with_options(&circuit, initial_witness(), &mut ForeignCallRegistry::new(), &options).unwrap();
+
+    // Bare bytecode is solved the same way, but without an ABI to decode the outputs nor names for the profile
+    let result =
+        execute::execute_with_options(circuit.bytecode.as_str(), initial_witness(), &mut ForeignCallRegistry::new(), &options)
+            .unwrap();
+    assert_eq!(result.witness_stack, expected.witness_stack);
+    assert!(result.public_inputs.is_empty());
+    let profile = result.profile.unwrap();
+    assert_eq!(profile.total_opcodes(), expected.profile.as_ref().unwrap().total_opcodes());
+    assert!(profile.functions[0].name.is_none());
+
+    // A prepared circuit keeps the compiled circuit it comes from
+    let prepared = circuit.prepare().unwrap();
+    let result = execute::execute_with_options(&prepared, initial_witness(), &mut ForeignCallRegistry::new(), &options).unwrap();
+    assert_eq!(result.public_inputs, expected.public_inputs);
+    assert_eq!(result.profile.unwrap().functions[0].name.as_deref(), Some("main"));
+}
+
+#[test]
+fn test_execution_limits() {
+    use crate::execute::{CancellationToken, ExecuteOptions, ExecutionError, ExecutionLimit};
+    use crate::foreign_calls::ForeignCallRegistry;
+    use acvm::acir::circuit::OpcodeLocation;
+
+    let circuit = compiled_circuit("unconstrained");
+    let execute = |options: &ExecuteOptions| {
+        let initial_witness = witness::from_vec_to_witness_map(vec![3_u128, 4_u128, 7_u128]).unwrap();
+        execute::execute_with_options(&circuit, initial_witness, &mut ForeignCallRegistry::new(), options)
+    };
+
+    // Generous limits do not change the result
+    let options = ExecuteOptions {
+        max_brillig_steps: Some(1_000_000),
+        deadline: Some(std::time::Instant::now() + std::time::Duration::from_secs(60)),
+        cancellation_token: Some(CancellationToken::new()),
+        ..Default::default()
+    };
+    let result = execute(&options).unwrap();
+    assert_eq!(result.public_inputs_json(&circuit.abi), json!({ "sum": 7 }));
+
+    let options = ExecuteOptions { max_brillig_steps: Some(1), ..Default::default() };
+    match execute(&options).unwrap_err() {
+        ExecutionError::LimitExceeded { limit, failure } => {
+            assert_eq!(limit, ExecutionLimit::BrilligSteps(1));
+            assert!(matches!(failure.opcode_location, OpcodeLocation::Brillig { .. }), "{:?}", failure.opcode_location);
+            assert_eq!(failure.message, "Execution exceeded the limit of 1 Brillig steps");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let options = ExecuteOptions { deadline: Some(std::time::Instant::now()), ..Default::default() };
+    assert!(matches!(execute(&options), Err(ExecutionError::LimitExceeded { limit: ExecutionLimit::Deadline, .. })));
+
+    let cancellation_token = CancellationToken::new();
+    cancellation_token.clone().cancel();
+    assert!(cancellation_token.is_cancelled());
+    let options = ExecuteOptions { cancellation_token: Some(cancellation_token), ..Default::default() };
+    assert!(matches!(execute(&options), Err(ExecutionError::LimitExceeded { limit: ExecutionLimit::Cancelled, .. })));
+}
+
+#[test]
+fn test_executor_matches_nargo() {
+    use bn254_blackbox_solver::Bn254BlackBoxSolver;
+    use nargo::foreign_calls::default::DefaultForeignCallBuilder;
+
+    // The executor of noir_rs mirrors the one of nargo, both must solve the circuits the same way
+    for name in ["product", "keccak", "unconstrained", "codegen"] {
+        let circuit = compiled_circuit(name);
+        let prover_toml = format!("circuits/crates/{}/Prover.toml", name);
+        let initial_witness = witness::read_prover_toml(&circuit.abi, prover_toml).unwrap();
+        let expected = nargo::ops::execute_program(
+            &circuit.program().unwrap(),
+            initial_witness.clone(),
+            &Bn254BlackBoxSolver::default(),
+            &mut DefaultForeignCallBuilder::default().build(),
+        )
+        .unwrap();
+        assert_eq!(execute::execute(&circuit, initial_witness).unwrap(), expected, "{}", name);
+    }
+}
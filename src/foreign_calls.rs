@@ -0,0 +1,612 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use acvm::{
+    acir::{
+        native_types::{Witness, WitnessMap},
+        FieldElement,
+    },
+    AcirField,
+};
+use noirc_printable_type::PrintableType;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult};
+pub use acvm::pwg::ForeignCallWaitInfo;
+pub use nargo::foreign_calls::ForeignCallError;
+
+use nargo::foreign_calls::print::{PrintForeignCallExecutor, PrintOutput};
+use nargo::foreign_calls::ForeignCallExecutor as _;
+
+use crate::abi::{input_value_to_json, Abi, AbiParameter, AbiType, AbiVisibility, Sign};
+
+/// Why a foreign call could not be answered
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ForeignCallFailure {
+    /// The executor does not handle the foreign call, which can be left to another executor
+    #[error("No handler could be found for foreign call `{0}`")]
+    Unhandled(String),
+    /// The executor handles the foreign call but could not answer it
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// A handler answering the foreign calls (oracles) of a circuit
+///
+/// Unlike the `ForeignCallExecutor` of nargo, it tells a foreign call it does not handle apart
+/// from one it failed to answer, so that handlers can be layered without hiding failures.
+/// The executors of nargo can be used as handlers through `NargoForeignCallExecutor`.
+pub trait ForeignCallHandler {
+    /// Answer a foreign call
+    ///
+    /// # Arguments
+    ///
+    /// * foreign_call: The name and inputs of the foreign call
+    ///
+    /// # Returns
+    ///
+    /// The result of the foreign call
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure>;
+}
+
+impl<E: ForeignCallHandler + ?Sized> ForeignCallHandler for &mut E {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        (**self).execute(foreign_call)
+    }
+}
+
+/// A foreign call executor of nargo used as a `ForeignCallHandler`, whose `NoHandler` errors
+/// are reported as unhandled foreign calls and any other error as a failure
+pub struct NargoForeignCallExecutor<E>(pub E);
+
+impl<E: nargo::foreign_calls::ForeignCallExecutor<FieldElement>> ForeignCallHandler for NargoForeignCallExecutor<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        self.0.execute(foreign_call).map_err(|e| match e {
+            ForeignCallError::NoHandler(function) => ForeignCallFailure::Unhandled(function),
+            e => ForeignCallFailure::Failed(e.to_string()),
+        })
+    }
+}
+
+/// A Rust function answering a foreign call (oracle) from its inputs
+pub type ForeignCallFn =
+    Box<dyn FnMut(&[ForeignCallParam<FieldElement>]) -> Result<ForeignCallResult<FieldElement>, String>>;
+
+/// A registry of Rust functions answering the foreign calls (oracles) of a circuit by name
+///
+/// Foreign calls without a handler are reported as unhandled, so that the registry can be
+/// composed with other executors (see `execute::execute_with_foreign_calls`). A handler
+/// returning an error fails the foreign call.
+#[derive(Default)]
+pub struct ForeignCallRegistry {
+    handlers: BTreeMap<String, ForeignCallFn>,
+}
+
+impl ForeignCallRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler of a foreign call, replacing any previous one
+    ///
+    /// # Arguments
+    ///
+    /// * name: The name of the foreign call, i.e. the name given to `#[oracle(...)]`
+    /// * handler: The function computing the result of the foreign call from its inputs
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&[ForeignCallParam<FieldElement>]) -> Result<ForeignCallResult<FieldElement>, String> + 'static,
+    ) -> &mut Self {
+        self.handlers.insert(name.into(), Box::new(handler));
+        self
+    }
+
+    /// Register the handler of a foreign call, consuming and returning the registry
+    pub fn with(
+        mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&[ForeignCallParam<FieldElement>]) -> Result<ForeignCallResult<FieldElement>, String> + 'static,
+    ) -> Self {
+        self.register(name, handler);
+        self
+    }
+
+    /// Check whether a foreign call has a handler
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+}
+
+impl ForeignCallHandler for ForeignCallRegistry {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        let Some(handler) = self.handlers.get_mut(&foreign_call.function) else {
+            return Err(ForeignCallFailure::Unhandled(foreign_call.function.clone()));
+        };
+        handler(&foreign_call.inputs)
+            .map_err(|e| ForeignCallFailure::Failed(format!("Foreign call `{}` failed: {}", foreign_call.function, e)))
+    }
+}
+
+/// The configuration of a JSON-RPC oracle resolver, the equivalent of `nargo execute --oracle-resolver`
+///
+/// Foreign calls are sent with the `resolve_foreign_call` method, following the protocol of nargo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleResolver {
+    /// The URL of the JSON-RPC endpoint
+    pub url: String,
+    /// The timeout of each request
+    pub timeout: Duration,
+    /// How many times a request is retried when the endpoint cannot be reached, times out
+    /// or answers with a server error
+    pub retries: u32,
+    /// The delay before the first retry, doubled before each following one
+    pub retry_delay: Duration,
+    /// The root path of the Noir project, sent along with each call
+    pub root_path: Option<PathBuf>,
+    /// The name of the Noir package, sent along with each call
+    pub package_name: Option<String>,
+}
+
+impl OracleResolver {
+    /// Create a resolver for the given endpoint, with a 30 second timeout and no retries
+    /// (retried after 200 ms, 400 ms, ... once `retries` is set)
+    pub fn new(url: impl Into<String>) -> Self {
+        OracleResolver {
+            url: url.into(),
+            timeout: Duration::from_secs(30),
+            retries: 0,
+            retry_delay: Duration::from_millis(200),
+            root_path: None,
+            package_name: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ResolveForeignCallRequest<'a> {
+    session_id: u64,
+    #[serde(flatten)]
+    function_call: &'a ForeignCallWaitInfo<FieldElement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_name: Option<String>,
+}
+
+/// A foreign call executor forwarding every foreign call to a JSON-RPC oracle resolver
+///
+/// Each executor has its own session id, so that the resolver can tell executions apart.
+pub struct JsonRpcForeignCallExecutor {
+    resolver: OracleResolver,
+    client: reqwest::blocking::Client,
+    session_id: u64,
+    request_id: u64,
+}
+
+impl JsonRpcForeignCallExecutor {
+    pub fn new(resolver: &OracleResolver) -> Result<Self, String> {
+        static SESSIONS: AtomicU64 = AtomicU64::new(0);
+        let client = reqwest::blocking::Client::builder()
+            .timeout(resolver.timeout)
+            .build()
+            .map_err(|e| format!("Failed to create the oracle resolver client: {}", e))?;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+        let session_id = nanos ^ ((std::process::id() as u64) << 32) ^ SESSIONS.fetch_add(1, Ordering::Relaxed);
+
+        Ok(JsonRpcForeignCallExecutor { resolver: resolver.clone(), client, session_id, request_id: 0 })
+    }
+
+    /// Get the session id sent along with each call
+    pub fn session_id(&self) -> u64 {
+        self.session_id
+    }
+
+    fn resolve(&mut self, foreign_call: &ForeignCallWaitInfo<FieldElement>) -> Result<ForeignCallResult<FieldElement>, String> {
+        self.request_id += 1;
+        let params = ResolveForeignCallRequest {
+            session_id: self.session_id,
+            function_call: foreign_call,
+            root_path: self.resolver.root_path.as_ref().map(|path| path.display().to_string()),
+            package_name: self.resolver.package_name.clone(),
+        };
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.request_id,
+            "method": "resolve_foreign_call",
+            "params": [params],
+        })
+        .to_string();
+
+        let mut attempt = 0;
+        let response = loop {
+            let sent = self
+                .client
+                .post(&self.resolver.url)
+                .header("Content-Type", "application/json")
+                .body(body.clone())
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text());
+            match sent {
+                Ok(response) => break response,
+                Err(e)
+                    if attempt < self.resolver.retries
+                        && (e.is_connect() || e.is_timeout() || e.status().is_some_and(|status| status.is_server_error())) =>
+                {
+                    std::thread::sleep(self.resolver.retry_delay.saturating_mul(1 << attempt.min(16)));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        };
+
+        let response: Value = serde_json::from_str(&response).map_err(|e| format!("invalid response: {}", e))?;
+        if let Some(error) = response.get("error") {
+            let message = error.get("message").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| error.to_string());
+            return Err(message);
+        }
+        let result = response.get("result").cloned().ok_or("invalid response: missing result")?;
+        serde_json::from_value(result).map_err(|e| format!("invalid result: {}", e))
+    }
+}
+
+impl ForeignCallHandler for JsonRpcForeignCallExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        self.resolve(foreign_call).map_err(|e| {
+            ForeignCallFailure::Failed(format!("Oracle resolver failed to resolve `{}`: {}", foreign_call.function, e))
+        })
+    }
+}
+
+/// A value printed by the circuit with `print` or `println`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrintedOutput {
+    /// The printed value, formatted as nargo would print it (without the trailing newline)
+    pub message: String,
+    /// Whether the value was printed with `println`
+    pub newline: bool,
+    /// The printed value, or each value interpolated into a printed format string
+    pub values: Vec<PrintedValue>,
+}
+
+/// A value printed by the circuit, decoded using the type information of the `print` call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrintedValue {
+    /// The type of the value (a serialized `noirc_printable_type::PrintableType`)
+    pub typ: Value,
+    /// The value as JSON, in the format of `abi::input_value_to_json`, or `None` if its
+    /// type has no ABI equivalent (e.g. a slice, a reference or a function)
+    pub value: Option<Value>,
+}
+
+/// Intercepts the `print` foreign calls to capture the printed values and/or emit them
+/// as `tracing` events (with the `noir_rs::print` target) instead of writing them to stdout.
+/// When neither is enabled, the calls are left to the default print executor.
+pub(crate) struct PrintCaptureForeignCallExecutor {
+    pub(crate) circuit_name: String,
+    pub(crate) capture: bool,
+    pub(crate) trace: bool,
+    pub(crate) output: Vec<PrintedOutput>,
+}
+
+impl ForeignCallHandler for PrintCaptureForeignCallExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        if foreign_call.function != "print" || !(self.capture || self.trace) {
+            return Err(ForeignCallFailure::Unhandled(foreign_call.function.clone()));
+        }
+        // Let nargo decode and format the value using the type information of the call
+        let mut printed = String::new();
+        let result = PrintForeignCallExecutor::new(PrintOutput::String(&mut printed))
+            .execute(foreign_call)
+            .map_err(|e| ForeignCallFailure::Failed(e.to_string()))?;
+
+        let newline = printed.ends_with('\n');
+        let message = printed.strip_suffix('\n').unwrap_or(&printed).to_string();
+        if self.trace {
+            tracing::info!(target: "noir_rs::print", circuit = %self.circuit_name, newline, "{}", message);
+        }
+        if self.capture {
+            let values = decode_printed_values(&foreign_call.inputs);
+            self.output.push(PrintedOutput { message, newline, values });
+        }
+        Ok(result)
+    }
+}
+
+/// Decode the values of a `print` call, whose inputs are `[newline, ...fields, type, false]`
+/// for a value and `[newline, message, count, ...fields, ...types, true]` for a format string
+fn decode_printed_values(inputs: &[ForeignCallParam<FieldElement>]) -> Vec<PrintedValue> {
+    let Some((ForeignCallParam::Single(is_format_string), inputs)) = inputs.get(1..).and_then(<[_]>::split_last) else {
+        return Vec::new();
+    };
+    let (values, types) = if is_format_string.is_one() {
+        let [_, ForeignCallParam::Single(count), inputs @ ..] = inputs else {
+            return Vec::new();
+        };
+        match inputs.len().checked_sub(count.to_u128() as usize) {
+            Some(split) => inputs.split_at(split),
+            None => return Vec::new(),
+        }
+    } else {
+        match inputs.split_last() {
+            Some((typ, values)) => (values, std::slice::from_ref(typ)),
+            None => return Vec::new(),
+        }
+    };
+
+    let mut fields = values.iter().flat_map(ForeignCallParam::fields);
+    let mut aligned = true;
+    types
+        .iter()
+        .map(|typ| {
+            let typ: String = typ.fields().iter().map(|field| field.to_u128() as u8 as char).collect();
+            let typ: Option<PrintableType> = serde_json::from_str(&typ).ok();
+            // The fields of the values after one that cannot be decoded cannot be located
+            let value = match typ.as_ref().and_then(abi_type) {
+                Some(abi_type) if aligned => decode_printed_value(&mut fields, abi_type),
+                _ => None,
+            };
+            aligned &= value.is_some();
+            PrintedValue { typ: typ.and_then(|typ| serde_json::to_value(typ).ok()).unwrap_or_default(), value }
+        })
+        .collect()
+}
+
+/// Decode a printed value from its fields, laid out as the parameters of a circuit
+fn decode_printed_value(fields: &mut impl Iterator<Item = FieldElement>, typ: AbiType) -> Option<Value> {
+    let witness_map: BTreeMap<Witness, FieldElement> = fields
+        .take(typ.field_count() as usize)
+        .enumerate()
+        .map(|(index, field)| (Witness(index as u32), field))
+        .collect();
+    let parameter = AbiParameter { name: "value".to_string(), typ: typ.clone(), visibility: AbiVisibility::Private };
+    let abi = Abi {
+        parameters: vec![parameter],
+        return_type: None,
+        error_types: BTreeMap::new(),
+    };
+    let (mut input_map, _) = abi.decode(&WitnessMap::from(witness_map)).ok()?;
+    Some(input_value_to_json(&input_map.remove("value")?, &typ))
+}
+
+/// Get the ABI type of a printable type, if it has one
+fn abi_type(typ: &PrintableType) -> Option<AbiType> {
+    Some(match typ {
+        PrintableType::Field => AbiType::Field,
+        PrintableType::Boolean => AbiType::Boolean,
+        PrintableType::UnsignedInteger { width } => AbiType::Integer { sign: Sign::Unsigned, width: *width },
+        PrintableType::SignedInteger { width } => AbiType::Integer { sign: Sign::Signed, width: *width },
+        PrintableType::String { length } => AbiType::String { length: *length },
+        PrintableType::Array { length, typ } => AbiType::Array { length: *length, typ: Box::new(abi_type(typ)?) },
+        PrintableType::Tuple { types } => AbiType::Tuple { fields: types.iter().map(abi_type).collect::<Option<_>>()? },
+        PrintableType::Struct { name, fields } => AbiType::Struct {
+            path: name.clone(),
+            fields: fields.iter().map(|(name, typ)| Some((name.clone(), abi_type(typ)?))).collect::<Option<_>>()?,
+        },
+        _ => return None,
+    })
+}
+
+/// Tries a first executor, and a second one for the foreign calls the first one does not handle
+pub(crate) struct FallbackForeignCallExecutor<A, B> {
+    pub(crate) primary: A,
+    pub(crate) fallback: Option<B>,
+}
+
+impl<A: ForeignCallHandler, B: ForeignCallHandler> ForeignCallHandler for FallbackForeignCallExecutor<A, B> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallFailure> {
+        match (self.primary.execute(foreign_call), &mut self.fallback) {
+            // A foreign call the primary executor failed to answer is not retried
+            (Err(ForeignCallFailure::Unhandled(_)), Some(fallback)) => fallback.execute(foreign_call),
+            (result, _) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::{native_types::Witness, FieldElement};
+    use serde_json::json;
+
+    use crate::test_utils::compiled_circuit;
+    use crate::{execute, witness};
+
+    #[test]
+    fn test_execute_with_foreign_calls() {
+        use crate::foreign_calls::{ForeignCallParam, ForeignCallRegistry, ForeignCallResult};
+
+        let oracle_circuit = compiled_circuit("oracle");
+        let initial_witness = || witness::from_vec_to_witness_map(vec![3_u128, 9_u128]).unwrap();
+
+        // The default executor does not know the oracle
+        assert!(execute::execute(&oracle_circuit, initial_witness()).is_err());
+
+        let mut registry = ForeignCallRegistry::new().with("get_square", |inputs| match inputs {
+            [ForeignCallParam::Single(x)] => Ok(ForeignCallResult { values: vec![ForeignCallParam::Single(*x * *x)] }),
+            _ => Err("expected a single field".to_string()),
+        });
+        assert!(registry.contains("get_square"));
+        let witness_stack = execute::execute_with_foreign_calls(&oracle_circuit, initial_witness(), &mut registry).unwrap();
+        assert_eq!(witness_stack.peek().unwrap().witness[&Witness(1)], FieldElement::from(9_u128));
+
+        // Handler errors are reported as is
+        registry.register("get_square", |_| Err("oracle unavailable".to_string()));
+        let err = execute::execute_with_foreign_calls(&oracle_circuit, initial_witness(), &mut registry).unwrap_err();
+        assert_eq!(err.to_string(), "Foreign call `get_square` failed: oracle unavailable");
+        assert!(matches!(err, execute::ExecutionError::ForeignCallFailed { ref function, .. } if function == "get_square"));
+
+        // A failed handler is not bypassed by the executors after it, e.g. an oracle resolver
+        // (listening on a port that was just released, so nothing answers there)
+        let unreachable = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let options = execute::ExecuteOptions {
+            oracle_resolver: Some(crate::foreign_calls::OracleResolver::new(format!("http://{}", unreachable))),
+            ..Default::default()
+        };
+        let err = execute::execute_with_options(&oracle_circuit, initial_witness(), &mut registry, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Foreign call `get_square` failed: oracle unavailable");
+
+        // Foreign calls without a handler are reported as such
+        let err = execute::execute_with_foreign_calls(&oracle_circuit, initial_witness(), &mut ForeignCallRegistry::new())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "No handler could be found for foreign call `get_square`");
+    }
+
+    /// Spawn a JSON-RPC oracle resolver answering `get_square` calls, failing the first
+    /// `failures` requests with an HTTP 503. Returns its URL and the received requests.
+    ///
+    /// The resolver gives up (and fails the test) if it is not called or the connection stalls.
+    fn spawn_oracle_resolver(
+        requests: usize,
+        failures: usize,
+    ) -> (String, std::thread::JoinHandle<Vec<serde_json::Value>>) {
+        use crate::foreign_calls::{ForeignCallParam, ForeignCallResult, ForeignCallWaitInfo};
+        use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+        use std::time::{Duration, Instant};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs(30);
+            let mut received = Vec::new();
+            for i in 0..requests + failures {
+                let stream = loop {
+                    match listener.accept() {
+                        Ok((stream, _)) => break stream,
+                        Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < deadline => {
+                            std::thread::sleep(Duration::from_millis(10))
+                        }
+                        Err(e) => panic!("The oracle resolver received {} of {} requests: {}", i, requests + failures, e),
+                    }
+                };
+                stream.set_nonblocking(false).unwrap();
+                stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+                stream.set_write_timeout(Some(Duration::from_secs(5))).unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+                let mut stream = reader.into_inner();
+                if i < failures {
+                    stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+                    continue;
+                }
+                let call: ForeignCallWaitInfo<FieldElement> = serde_json::from_value(request["params"][0].clone()).unwrap();
+                let ForeignCallParam::Single(x) = call.inputs[0] else { panic!("expected a single field") };
+                let result = ForeignCallResult { values: vec![ForeignCallParam::Single(x * x)] };
+                let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+                received.push(request);
+            }
+            received
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_execute_with_oracle_resolver() {
+        use crate::foreign_calls::{ForeignCallRegistry, OracleResolver};
+
+        let oracle_circuit = compiled_circuit("oracle");
+        let (url, resolver) = spawn_oracle_resolver(2, 1);
+
+        let mut oracle_resolver = OracleResolver::new(url);
+        oracle_resolver.timeout = std::time::Duration::from_secs(5);
+        oracle_resolver.retries = 1;
+        let options = execute::ExecuteOptions { oracle_resolver: Some(oracle_resolver), ..Default::default() };
+
+        for _ in 0..2 {
+            let initial_witness = witness::from_vec_to_witness_map(vec![3_u128, 9_u128]).unwrap();
+            let result =
+                execute::execute_with_options(&oracle_circuit, initial_witness, &mut ForeignCallRegistry::new(), &options)
+                    .unwrap();
+            assert_eq!(result.public_inputs_json(&oracle_circuit.abi), json!({ "square": format!("0x{:0>64}", "09") }));
+        }
+
+        let requests = resolver.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0]["method"], json!("resolve_foreign_call"));
+        assert_eq!(requests[0]["params"][0]["function"], json!("get_square"));
+        // Each execution has its own session
+        assert_ne!(requests[0]["params"][0]["session_id"], requests[1]["params"][0]["session_id"]);
+    }
+
+    #[test]
+    fn test_capture_output() {
+        use crate::foreign_calls::{ForeignCallRegistry, PrintedOutput};
+
+        let circuit = compiled_circuit("print");
+        let initial_witness = || witness::from_vec_to_witness_map(vec![3_u128, 4_u128, 12_u128]).unwrap();
+
+        let options = execute::ExecuteOptions { capture_output: true, ..Default::default() };
+        let result = execute::execute_with_options(&circuit, initial_witness(), &mut ForeignCallRegistry::new(), &options).unwrap();
+        let values = |output: &PrintedOutput| output.values.iter().map(|value| value.value.clone()).collect::<Vec<_>>();
+        assert_eq!(result.output.len(), 3);
+
+        assert_eq!((result.output[0].message.as_str(), result.output[0].newline), ("12", true));
+        assert_eq!(values(&result.output[0]), vec![Some(json!(12))]);
+        assert!(!result.output[0].values[0].typ.is_null());
+
+        // Each value interpolated into a format string is decoded
+        assert_eq!((result.output[1].message.as_str(), result.output[1].newline), ("3 * 4 = 12", false));
+        assert_eq!(values(&result.output[1]), vec![Some(json!(3)), Some(json!(4)), Some(json!(12))]);
+
+        assert!(result.output[2].newline);
+        assert_eq!(values(&result.output[2]), vec![Some(json!({ "x": format!("0x{:0>64}", "03"), "y": 4 }))]);
+
+        // Nothing is captured by default
+        let result = execute::execute_with_options(
+            &circuit,
+            initial_witness(),
+            &mut ForeignCallRegistry::new(),
+            &execute::ExecuteOptions::default(),
+        )
+        .unwrap();
+        assert!(result.output.is_empty());
+    }
+}
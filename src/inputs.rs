@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::{native_types::WitnessMap, FieldElement};
+use acvm::AcirField;
+use serde_json::Value;
+
+use crate::abi::{parse_json_inputs, Abi, InputMap};
+use crate::witness::from_json_to_witness_map;
+
+#[cfg(feature = "derive")]
+pub use noir_rs_derive::{NoirInputs, ToNoirValue};
+
+// Used by the code generated by the derive macros
+#[doc(hidden)]
+pub use serde_json;
+
+/// A Rust value that can be used as the value of a circuit input
+///
+/// Integers and booleans map onto their Noir counterparts, strings onto `str<N>`,
+/// arrays and `Vec`s onto arrays (a `Vec` can also be used for a `BoundedVec` of
+/// larger capacity), and tuples onto tuples. Structs can implement it with
+/// `#[derive(ToNoirValue)]` (requires the `derive` feature).
+///
+/// The value is only checked against the ABI of the circuit when it is encoded.
+pub trait ToNoirValue {
+    fn to_noir_value(&self) -> Value;
+}
+
+/// A Rust struct whose fields are the named inputs of a circuit
+///
+/// It can be implemented with `#[derive(NoirInputs)]` (requires the `derive` feature).
+pub trait NoirInputs {
+    /// Get the inputs as a JSON object mapping each parameter name to its value
+    fn to_noir_inputs(&self) -> Value;
+
+    /// Encode the inputs into an input map, checking them against the ABI of the circuit
+    fn to_input_map(&self, abi: &Abi) -> Result<InputMap, String> {
+        parse_json_inputs(abi, &self.to_noir_inputs())
+    }
+
+    /// Encode the inputs into a witness map, checking them against the ABI of the circuit
+    fn to_witness_map(&self, abi: &Abi) -> Result<WitnessMap<FieldElement>, String> {
+        from_json_to_witness_map(abi, &self.to_noir_inputs())
+    }
+}
+
+/// A Rust value that can be decoded from the value of a circuit output, as returned by
+/// `abi::input_value_to_json` (e.g. the public inputs or return value of an execution)
+///
+/// It mirrors `ToNoirValue`: a `Vec` can also be decoded from a `BoundedVec`, in which case
+/// only its first `len` items are kept.
+pub trait FromNoirValue: Sized {
+    fn from_noir_value(value: &Value) -> Result<Self, String>;
+}
+
+/// Decode a member of a Noir struct
+///
+/// # Arguments
+///
+/// * value: The JSON object of the struct
+/// * name: The name of the member
+///
+/// # Returns
+///
+/// The decoded member
+pub fn get_noir_member<T: FromNoirValue>(value: &Value, name: &str) -> Result<T, String> {
+    let member = value.get(name).ok_or_else(|| format!("missing member `{}`", name))?;
+    T::from_noir_value(member).map_err(|e| format!("{}: {}", name, e))
+}
+
+macro_rules! impl_to_noir_value_for_number {
+    ($($t:ty),*) => {
+        $(
+            impl ToNoirValue for $t {
+                fn to_noir_value(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_noir_value_for_number!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+macro_rules! impl_from_noir_value_for_number {
+    ($($t:ty),*) => {
+        $(
+            impl FromNoirValue for $t {
+                fn from_noir_value(value: &Value) -> Result<Self, String> {
+                    let parsed = match value {
+                        Value::Number(number) => number.to_string().parse::<$t>().ok(),
+                        Value::String(string) => string.parse::<$t>().ok(),
+                        _ => None,
+                    };
+                    parsed.ok_or_else(|| format!("expected {}, got {}", stringify!($t), value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_noir_value_for_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl ToNoirValue for u128 {
+    fn to_noir_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToNoirValue for i128 {
+    fn to_noir_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToNoirValue for bool {
+    fn to_noir_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToNoirValue for FieldElement {
+    fn to_noir_value(&self) -> Value {
+        Value::String(format!("0x{}", self.to_hex()))
+    }
+}
+
+impl FromNoirValue for bool {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        value.as_bool().ok_or_else(|| format!("expected a boolean, got {}", value))
+    }
+}
+
+impl FromNoirValue for FieldElement {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        let parsed = match value {
+            Value::Number(number) => FieldElement::try_from_str(&number.to_string()),
+            Value::String(string) => FieldElement::try_from_str(string),
+            _ => None,
+        };
+        parsed.ok_or_else(|| format!("expected a field element, got {}", value))
+    }
+}
+
+impl FromNoirValue for String {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        value.as_str().map(str::to_string).ok_or_else(|| format!("expected a string, got {}", value))
+    }
+}
+
+impl ToNoirValue for str {
+    fn to_noir_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToNoirValue for String {
+    fn to_noir_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl<T: ToNoirValue + ?Sized> ToNoirValue for &T {
+    fn to_noir_value(&self) -> Value {
+        (**self).to_noir_value()
+    }
+}
+
+impl<T: ToNoirValue, const N: usize> ToNoirValue for [T; N] {
+    fn to_noir_value(&self) -> Value {
+        Value::Array(self.iter().map(ToNoirValue::to_noir_value).collect())
+    }
+}
+
+impl<T: ToNoirValue> ToNoirValue for [T] {
+    fn to_noir_value(&self) -> Value {
+        Value::Array(self.iter().map(ToNoirValue::to_noir_value).collect())
+    }
+}
+
+impl<T: ToNoirValue> ToNoirValue for Vec<T> {
+    fn to_noir_value(&self) -> Value {
+        self.as_slice().to_noir_value()
+    }
+}
+
+impl<T: FromNoirValue> FromNoirValue for Vec<T> {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        if let (Some(storage), Some(len)) = (value.get("storage"), value.get("len")) {
+            let len = usize::try_from(u64::from_noir_value(len)?).map_err(|e| e.to_string())?;
+            let mut items = Vec::from_noir_value(storage)?;
+            items.truncate(len);
+            return Ok(items);
+        }
+        let items = value.as_array().ok_or_else(|| format!("expected an array, got {}", value))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| T::from_noir_value(item).map_err(|e| format!("[{}]: {}", i, e)))
+            .collect()
+    }
+}
+
+impl<T: FromNoirValue, const N: usize> FromNoirValue for [T; N] {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        let items = Vec::<T>::from_noir_value(value)?;
+        let len = items.len();
+        items.try_into().map_err(|_| format!("expected an array of length {}, got {}", N, len))
+    }
+}
+
+impl<V: ToNoirValue> ToNoirValue for BTreeMap<String, V> {
+    fn to_noir_value(&self) -> Value {
+        Value::Object(self.iter().map(|(name, value)| (name.clone(), value.to_noir_value())).collect())
+    }
+}
+
+impl ToNoirValue for Value {
+    fn to_noir_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromNoirValue for Value {
+    fn from_noir_value(value: &Value) -> Result<Self, String> {
+        Ok(value.clone())
+    }
+}
+
+macro_rules! impl_to_noir_value_for_tuple {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: ToNoirValue),+> ToNoirValue for ($($name,)+) {
+            fn to_noir_value(&self) -> Value {
+                Value::Array(vec![$(self.$index.to_noir_value()),+])
+            }
+        }
+    };
+}
+
+macro_rules! impl_from_noir_value_for_tuple {
+    ($len:literal; $($name:ident : $index:tt),+) => {
+        impl<$($name: FromNoirValue),+> FromNoirValue for ($($name,)+) {
+            fn from_noir_value(value: &Value) -> Result<Self, String> {
+                match value.as_array() {
+                    Some(items) if items.len() == $len => Ok(($($name::from_noir_value(&items[$index])?,)+)),
+                    _ => Err(format!("expected a tuple of {} elements, got {}", $len, value)),
+                }
+            }
+        }
+    };
+}
+
+impl_to_noir_value_for_tuple!(A: 0);
+impl_to_noir_value_for_tuple!(A: 0, B: 1);
+impl_to_noir_value_for_tuple!(A: 0, B: 1, C: 2);
+impl_to_noir_value_for_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+impl_from_noir_value_for_tuple!(1; A: 0);
+impl_from_noir_value_for_tuple!(2; A: 0, B: 1);
+impl_from_noir_value_for_tuple!(3; A: 0, B: 1, C: 2);
+impl_from_noir_value_for_tuple!(4; A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::FieldElement;
+    use serde_json::json;
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_noir_inputs() {
+        use crate::inputs::{NoirInputs, ToNoirValue};
+        use crate::{test_utils::sample_abi, witness};
+
+        #[derive(ToNoirValue)]
+        struct Items {
+            storage: [u64; 3],
+            len: u32,
+        }
+
+        #[derive(NoirInputs)]
+        struct Inputs {
+            x: i8,
+            flag: bool,
+            name: String,
+            items: Items,
+            #[noir(rename = "result")]
+            product: u64,
+            #[noir(skip)]
+            _comment: &'static str,
+        }
+
+        let inputs = Inputs {
+            x: -1,
+            flag: true,
+            name: "abc".to_string(),
+            items: Items { storage: [1, 2, 0], len: 2 },
+            product: 30,
+            _comment: "not an input",
+        };
+        let abi = sample_abi();
+        let witness_map = inputs.to_witness_map(&abi).unwrap();
+        let expected = witness::from_json_to_witness_map(
+            &abi,
+            &json!({ "x": -1, "flag": true, "name": "abc", "items": ["1", "2"], "result": "30" }),
+        )
+        .unwrap();
+        assert_eq!(witness_map, expected);
+    }
+
+    #[test]
+    fn test_from_noir_value() {
+        use crate::inputs::{FromNoirValue, ToNoirValue};
+
+        let field = FieldElement::from(30_u128);
+        assert_eq!(FieldElement::from_noir_value(&field.to_noir_value()).unwrap(), field);
+        assert_eq!(i8::from_noir_value(&json!(-5)).unwrap(), -5);
+        assert_eq!(u128::from_noir_value(&json!("340282366920938463463374607431768211455")).unwrap(), u128::MAX);
+        assert!(u8::from_noir_value(&json!(256)).is_err());
+        assert_eq!(<[u8; 2]>::from_noir_value(&json!([1, 2])).unwrap(), [1, 2]);
+        assert!(<[u8; 3]>::from_noir_value(&json!([1, 2])).is_err());
+        assert_eq!(<(bool, String)>::from_noir_value(&json!([true, "abc"])).unwrap(), (true, "abc".to_string()));
+        // BoundedVecs are truncated to their length
+        assert_eq!(Vec::<u32>::from_noir_value(&json!({ "storage": [1, 2, 0], "len": 2 })).unwrap(), vec![1, 2]);
+    }
+}
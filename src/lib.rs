@@ -1,9 +1,22 @@
+// Lets the code generated by `noir_rs_derive` refer to `::noir_rs` from within this crate
+extern crate self as noir_rs;
+
 pub use acvm::*;
 
+pub mod abi;
 pub mod execute;
 pub mod witness;
 pub mod circuit; 
+pub mod codegen;
+pub mod debug;
+pub mod foreign_calls;
+pub mod inputs;
+pub mod serialization;
+pub mod validation;
 mod backends;
 
 #[cfg(feature = "barretenberg")]
 pub use backends::barretenberg;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,147 @@
+use std::{
+    fmt,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The magic bytes at the start of any gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The serialization formats understood by Noir and barretenberg
+///
+/// Except for the legacy bincode encoding, the serialized data starts
+/// with a marker byte identifying the format (the discriminant of this enum).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SerializationFormat {
+    /// Bincode without any format marker, as produced by older versions of Noir
+    BincodeLegacy = 0,
+    /// Bincode
+    Bincode = 1,
+    /// Msgpack with named struct fields
+    Msgpack = 2,
+    /// Msgpack with struct fields as arrays, as expected by barretenberg
+    MsgpackCompact = 3,
+}
+
+impl SerializationFormat {
+    /// Get the format from its marker byte
+    pub fn from_marker(marker: u8) -> Option<Self> {
+        match marker {
+            1 => Some(SerializationFormat::Bincode),
+            2 => Some(SerializationFormat::Msgpack),
+            3 => Some(SerializationFormat::MsgpackCompact),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SerializationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SerializationFormat::BincodeLegacy => "bincode-legacy",
+            SerializationFormat::Bincode => "bincode",
+            SerializationFormat::Msgpack => "msgpack",
+            SerializationFormat::MsgpackCompact => "msgpack-compact",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SerializationFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bincode-legacy" => Ok(SerializationFormat::BincodeLegacy),
+            "bincode" => Ok(SerializationFormat::Bincode),
+            "msgpack" => Ok(SerializationFormat::Msgpack),
+            "msgpack-compact" => Ok(SerializationFormat::MsgpackCompact),
+            _ => Err(format!("Unknown serialization format: {}", s)),
+        }
+    }
+}
+
+/// Serialize a value in the given format, prefixed with its format marker
+///
+/// # Arguments
+///
+/// * value: The value to serialize
+/// * format: The serialization format to use
+///
+/// # Returns
+///
+/// The serialized value (uncompressed)
+pub fn serialize_with_format<T: Serialize>(value: &T, format: SerializationFormat) -> Result<Vec<u8>, String> {
+    let payload = match format {
+        SerializationFormat::BincodeLegacy | SerializationFormat::Bincode => {
+            bincode::serialize(value).map_err(|e| e.to_string())?
+        }
+        SerializationFormat::Msgpack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string())?,
+        SerializationFormat::MsgpackCompact => rmp_serde::to_vec(value).map_err(|e| e.to_string())?,
+    };
+    if format == SerializationFormat::BincodeLegacy {
+        return Ok(payload);
+    }
+    let mut buf = Vec::with_capacity(payload.len() + 1);
+    buf.push(format as u8);
+    buf.extend(payload);
+    Ok(buf)
+}
+
+/// Deserialize a value serialized in any of the supported formats
+///
+/// Gzip compression is detected and undone first, then the format is
+/// read from the marker byte, falling back to legacy bincode.
+///
+/// # Arguments
+///
+/// * buf: The serialized value
+///
+/// # Returns
+///
+/// The value and the format it was serialized with
+pub fn deserialize_any_format<T: DeserializeOwned>(buf: &[u8]) -> Result<(T, SerializationFormat), String> {
+    if is_compressed(buf) {
+        return deserialize_any_format(&decompress(buf)?);
+    }
+    if let Some((marker, payload)) = buf.split_first() {
+        // Legacy bincode data may start with a byte that looks like a marker,
+        // so only trust the marker if the payload can be decoded with it
+        let decoded = match SerializationFormat::from_marker(*marker) {
+            Some(SerializationFormat::Bincode) => bincode::deserialize(payload).ok(),
+            Some(SerializationFormat::Msgpack | SerializationFormat::MsgpackCompact) => {
+                rmp_serde::from_slice(payload).ok()
+            }
+            _ => None,
+        };
+        if let (Some(value), Some(format)) = (decoded, SerializationFormat::from_marker(*marker)) {
+            return Ok((value, format));
+        }
+    }
+    let value = bincode::deserialize(buf).map_err(|e| format!("Failed to deserialize: {}", e))?;
+    Ok((value, SerializationFormat::BincodeLegacy))
+}
+
+/// Check whether the buffer is gzip-compressed
+pub fn is_compressed(buf: &[u8]) -> bool {
+    buf.starts_with(&GZIP_MAGIC)
+}
+
+/// Compress the buffer with gzip
+pub fn compress(buf: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(buf).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+/// Decompress a gzip-compressed buffer
+pub fn decompress(buf: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoder = GzDecoder::new(buf);
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed).map_err(|e| e.to_string())?;
+    Ok(decompressed)
+}
@@ -0,0 +1,100 @@
+//! Fixtures shared by the tests of the crate
+
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde_json::json;
+
+use crate::{abi, circuit::CompiledCircuit};
+
+/// Get the path to the artifact of a test circuit, compiled from `circuits/crates/<name>`
+pub(crate) fn artifact_path(name: &str) -> String {
+    format!("circuits/target/{}.json", name)
+}
+
+/// Load the artifact of a test circuit (see `artifact_path`)
+pub(crate) fn compiled_circuit(name: &str) -> CompiledCircuit {
+    CompiledCircuit::from_file(artifact_path(name)).unwrap()
+}
+
+/// Read the artifact of a test circuit as JSON, e.g. to tamper with it
+pub(crate) fn artifact_json(name: &str) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(artifact_path(name)).unwrap()).unwrap()
+}
+
+/// A path in the temporary directory unique to the test process and call, so that tests running
+/// concurrently do not overwrite each other's files. The file is removed when the path is dropped,
+/// even if the test fails.
+pub(crate) struct TempPath(PathBuf);
+
+impl TempPath {
+    pub(crate) fn new(name: &str) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        TempPath(std::env::temp_dir().join(format!("noir_rs_test_{}_{}_{}", std::process::id(), id, name)))
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// An ABI covering the integer, boolean, string and BoundedVec encodings
+pub(crate) fn sample_abi() -> abi::Abi {
+    abi::parse_abi(&json!({
+        "parameters": [
+            { "name": "x", "type": { "kind": "integer", "sign": "signed", "width": 8 }, "visibility": "private" },
+            { "name": "flag", "type": { "kind": "boolean" }, "visibility": "private" },
+            { "name": "name", "type": { "kind": "string", "length": 3 }, "visibility": "private" },
+            {
+                "name": "items",
+                "type": {
+                    "kind": "struct",
+                    "path": "std::collections::bounded_vec::BoundedVec",
+                    "fields": [
+                        { "name": "storage", "type": { "kind": "array", "length": 3, "type": { "kind": "field" } } },
+                        { "name": "len", "type": { "kind": "integer", "sign": "unsigned", "width": 32 } }
+                    ]
+                },
+                "visibility": "private"
+            },
+            { "name": "result", "type": { "kind": "field" }, "visibility": "public" }
+        ],
+        "return_type": null,
+        "error_types": {}
+    }))
+    .unwrap()
+}
+
+/// The ABI of the `product` circuit
+pub(crate) fn product_abi() -> abi::Abi {
+    abi::parse_abi(&json!({
+        "parameters": [
+            { "name": "a", "type": { "kind": "field" }, "visibility": "private" },
+            { "name": "b", "type": { "kind": "field" }, "visibility": "private" },
+            { "name": "result", "type": { "kind": "field" }, "visibility": "public" }
+        ],
+        "return_type": null,
+        "error_types": {}
+    }))
+    .unwrap()
+}
@@ -0,0 +1,420 @@
+use std::fmt;
+
+use acvm::{
+    acir::{
+        circuit::Program,
+        native_types::{Witness, WitnessMap},
+        FieldElement,
+    },
+    AcirField,
+};
+use serde_json::Value;
+
+use crate::abi::{is_bounded_vec, Abi, AbiType, Sign, MAIN_RETURN_NAME};
+
+/// A problem found while validating the inputs of a circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    /// The path of the offending value, e.g. `msg[3]` or `passport.dob.year`
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl InputError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        InputError { path: path.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Join a list of input errors into a single message, one error per line
+pub fn format_input_errors(errors: &[InputError]) -> String {
+    errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+/// Validate named inputs given as a JSON object against the ABI of the circuit
+///
+/// Every parameter is checked: missing and unexpected parameters or struct fields,
+/// array and string lengths, integer bit widths, booleans and field elements
+/// exceeding the field modulus. All problems are reported at once.
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: A JSON object mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The list of problems found, if any
+pub fn validate_inputs(abi: &Abi, inputs: &Value) -> Result<(), Vec<InputError>> {
+    let mut errors = Vec::new();
+    let Value::Object(inputs) = inputs else {
+        return Err(vec![InputError::new("", "expected an object mapping parameter names to values")]);
+    };
+
+    for param in &abi.parameters {
+        match inputs.get(&param.name) {
+            Some(value) => validate_value(&param.typ, value, &param.name, &mut errors),
+            None => errors.push(InputError::new(&param.name, "missing parameter")),
+        }
+    }
+    if let (Some(return_type), Some(value)) = (&abi.return_type, inputs.get(MAIN_RETURN_NAME)) {
+        validate_value(&return_type.abi_type, value, MAIN_RETURN_NAME, &mut errors);
+    }
+    for name in inputs.keys() {
+        let is_return = name == MAIN_RETURN_NAME && abi.return_type.is_some();
+        if !is_return && !abi.parameters.iter().any(|param| &param.name == name) {
+            errors.push(InputError::new(name, "unexpected parameter"));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate named inputs written in TOML (e.g. the content of a `Prover.toml`) against the ABI of the circuit
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: The TOML string mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The list of problems found, if any
+pub fn validate_toml_inputs(abi: &Abi, inputs: &str) -> Result<(), Vec<InputError>> {
+    let inputs: Value = toml::from_str(inputs).map_err(|e| vec![InputError::new("", format!("invalid TOML: {}", e))])?;
+    validate_inputs(abi, &inputs)
+}
+
+/// Validate that a witness map provides exactly the witnesses expected
+/// as parameters by the main function of the program
+///
+/// The execute functions taking a raw `WitnessMap` do not call it: a missing witness only
+/// fails the execution once an opcode needs it, and an unexpected one is ignored. Call it
+/// beforehand to reject such witness maps up front.
+///
+/// # Arguments
+///
+/// * program: The program to execute
+/// * witness_map: The initial witness map
+///
+/// # Returns
+///
+/// The list of problems found, if any
+pub fn validate_witness_map(
+    program: &Program<FieldElement>,
+    witness_map: &WitnessMap<FieldElement>,
+) -> Result<(), Vec<InputError>> {
+    let main = program.functions.first().ok_or_else(|| vec![InputError::new("", "the program has no functions")])?;
+    let mut expected: Vec<Witness> = main.private_parameters.iter().copied().collect();
+    expected.extend(main.public_parameters.0.iter().copied());
+
+    let mut errors: Vec<InputError> = expected
+        .iter()
+        .filter(|witness| !witness_map.contains_key(witness))
+        .map(|witness| InputError::new(&format!("_{}", witness.witness_index()), "missing witness value"))
+        .collect();
+    // Return values may be provided as well, to be checked against the execution
+    for (witness, _) in witness_map.iter() {
+        if !expected.contains(witness) && !main.return_values.0.contains(witness) {
+            errors.push(InputError::new(&format!("_{}", witness.witness_index()), "unexpected witness"));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_value(typ: &AbiType, value: &Value, path: &str, errors: &mut Vec<InputError>) {
+    match typ {
+        AbiType::Field => match parse_number(value) {
+            Some((_, magnitude)) if exceeds_modulus(&magnitude) => {
+                errors.push(InputError::new(path, "value exceeds the field modulus"))
+            }
+            Some(_) => {}
+            None => errors.push(InputError::new(path, format!("expected a field element, got {}", value))),
+        },
+        AbiType::Integer { sign, width } => validate_integer(sign, *width, value, path, errors),
+        AbiType::Boolean => {
+            let is_bool = match value {
+                Value::Bool(_) => true,
+                Value::Number(number) => number.as_u64().is_some_and(|n| n <= 1),
+                Value::String(string) => matches!(string.as_str(), "0" | "1"),
+                _ => false,
+            };
+            if !is_bool {
+                errors.push(InputError::new(path, format!("expected a boolean (0 or 1), got {}", value)));
+            }
+        }
+        AbiType::String { length } => match value {
+            Value::String(string) if string.len() == *length as usize => {}
+            Value::String(string) => errors.push(InputError::new(
+                path,
+                format!("expected a string of {} bytes, got {} bytes", length, string.len()),
+            )),
+            _ => errors.push(InputError::new(path, format!("expected a string, got {}", value))),
+        },
+        AbiType::Array { length, typ } => match value {
+            Value::Array(items) => {
+                if items.len() != *length as usize {
+                    errors.push(InputError::new(
+                        path,
+                        format!("expected an array of {} items, got {} items", length, items.len()),
+                    ));
+                }
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(typ, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+            _ => errors.push(InputError::new(path, format!("expected an array, got {}", value))),
+        },
+        AbiType::Tuple { fields } => match value {
+            Value::Array(items) => {
+                if items.len() != fields.len() {
+                    errors.push(InputError::new(
+                        path,
+                        format!("expected a tuple of {} items, got {} items", fields.len(), items.len()),
+                    ));
+                }
+                for (i, (typ, item)) in fields.iter().zip(items).enumerate() {
+                    validate_value(typ, item, &format!("{}.{}", path, i), errors);
+                }
+            }
+            _ => errors.push(InputError::new(path, format!("expected a tuple, got {}", value))),
+        },
+        AbiType::Struct { path: struct_path, fields } => match value {
+            // A BoundedVec can be given as a plain array of at most its capacity
+            Value::Array(items) if is_bounded_vec(struct_path) => {
+                if let Some((_, AbiType::Array { length, typ })) = fields.iter().find(|(name, _)| name == "storage") {
+                    if items.len() > *length as usize {
+                        errors.push(InputError::new(
+                            path,
+                            format!("expected at most {} items, got {} items", length, items.len()),
+                        ));
+                    }
+                    for (i, item) in items.iter().enumerate() {
+                        validate_value(typ, item, &format!("{}[{}]", path, i), errors);
+                    }
+                }
+            }
+            Value::Object(object) => {
+                for (name, typ) in fields {
+                    let field_path = format!("{}.{}", path, name);
+                    match object.get(name) {
+                        Some(value) => validate_value(typ, value, &field_path, errors),
+                        None => errors.push(InputError::new(&field_path, "missing field")),
+                    }
+                }
+                for name in object.keys() {
+                    if !fields.iter().any(|(field_name, _)| field_name == name) {
+                        errors.push(InputError::new(&format!("{}.{}", path, name), "unexpected field"));
+                    }
+                }
+            }
+            _ => errors.push(InputError::new(path, format!("expected a struct, got {}", value))),
+        },
+    }
+}
+
+fn validate_integer(sign: &Sign, width: u32, value: &Value, path: &str, errors: &mut Vec<InputError>) {
+    let type_name = match sign {
+        Sign::Unsigned => format!("u{}", width),
+        Sign::Signed => format!("i{}", width),
+    };
+    let Some((negative, magnitude)) = parse_number(value) else {
+        errors.push(InputError::new(path, format!("expected an integer ({}), got {}", type_name, value)));
+        return;
+    };
+    let magnitude = match to_u128(&magnitude) {
+        Some(magnitude) => magnitude,
+        None => {
+            errors.push(InputError::new(path, format!("value {} does not fit in {}", value, type_name)));
+            return;
+        }
+    };
+    let in_range = match sign {
+        Sign::Unsigned => !negative && (width >= 128 || magnitude < 1 << width),
+        // Only zero fits in 0 bits, and any magnitude that fits in a u128 fits past i128
+        Sign::Signed if width == 0 => magnitude == 0,
+        Sign::Signed if width > 128 => true,
+        Sign::Signed if negative => magnitude <= 1 << (width - 1),
+        Sign::Signed => magnitude < 1 << (width - 1),
+    };
+    if !in_range {
+        errors.push(InputError::new(path, format!("value {} does not fit in {}", value, type_name)));
+    }
+}
+
+/// Parse a JSON number or a decimal/hexadecimal string into its sign and its big-endian magnitude
+fn parse_number(value: &Value) -> Option<(bool, Vec<u8>)> {
+    let string = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => string.clone(),
+        _ => return None,
+    };
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, string.as_str()),
+    };
+    let magnitude = match digits.strip_prefix("0x") {
+        Some("") => return None,
+        Some(hex_digits) => {
+            let hex_digits = if hex_digits.len() % 2 == 1 { format!("0{}", hex_digits) } else { hex_digits.to_string() };
+            hex::decode(hex_digits).ok()?
+        }
+        None => parse_decimal(digits)?,
+    };
+    Some((negative, magnitude))
+}
+
+/// Parse a decimal string into a big-endian byte vector
+fn parse_decimal(digits: &str) -> Option<Vec<u8>> {
+    if digits.is_empty() {
+        return None;
+    }
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in digits.chars() {
+        let mut carry = c.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    Some(bytes)
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn to_u128(bytes: &[u8]) -> Option<u128> {
+    let bytes = strip_leading_zeros(bytes);
+    if bytes.len() > 16 {
+        return None;
+    }
+    Some(bytes.iter().fold(0_u128, |acc, byte| (acc << 8) | *byte as u128))
+}
+
+/// Check whether a big-endian magnitude is greater than or equal to the field modulus
+fn exceeds_modulus(bytes: &[u8]) -> bool {
+    let max_value = (FieldElement::zero() - FieldElement::one()).to_be_bytes();
+    let max_value = strip_leading_zeros(&max_value);
+    let bytes = strip_leading_zeros(bytes);
+    // Both are big-endian without leading zeros, so the longest one is the biggest
+    (bytes.len(), bytes) > (max_value.len(), max_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::{native_types::{Witness, WitnessMap}, FieldElement};
+    use serde_json::json;
+
+    use crate::test_utils::{compiled_circuit, sample_abi};
+    use crate::{abi, validation, witness};
+
+    #[test]
+    fn test_validate_inputs_reports_every_error() {
+        let abi = sample_abi();
+        let inputs = json!({
+            "x": 128,
+            "flag": 2,
+            "name": "abcd",
+            "items": ["1", "0xzz", "3", "4"],
+            "extra": "1"
+        });
+        let errors = validation::validate_inputs(&abi, &inputs).unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["x", "flag", "name", "items", "items[1]", "result", "extra"]);
+
+        // The encoder reports the same errors instead of silently zeroing values
+        let message = witness::from_json_to_witness_map(&abi, &inputs).unwrap_err();
+        assert!(message.contains("items[1]: expected a field element"));
+    }
+
+    #[test]
+    fn test_validate_numbers() {
+        let integer = |sign: &str, width: u32| {
+            abi::parse_abi(&json!({
+                "parameters": [
+                    { "name": "x", "type": { "kind": "integer", "sign": sign, "width": width }, "visibility": "private" },
+                    { "name": "f", "type": { "kind": "field" }, "visibility": "private" }
+                ],
+                "return_type": null,
+                "error_types": {}
+            }))
+            .unwrap()
+        };
+        let error_paths = |abi: &abi::Abi, inputs: serde_json::Value| -> Vec<String> {
+            match validation::validate_inputs(abi, &inputs) {
+                Ok(()) => Vec::new(),
+                Err(errors) => errors.into_iter().map(|e| e.path).collect(),
+            }
+        };
+
+        // A hexadecimal prefix without digits is not a number
+        let i8_abi = integer("signed", 8);
+        assert_eq!(error_paths(&i8_abi, json!({ "x": "0x", "f": "-0x" })), vec!["x", "f"]);
+        assert!(error_paths(&i8_abi, json!({ "x": "-0x80", "f": "0x0" })).is_empty());
+        assert_eq!(error_paths(&i8_abi, json!({ "x": "-0x81", "f": "0" })), vec!["x"]);
+
+        // Degenerate widths do not overflow
+        let i0_abi = integer("signed", 0);
+        assert!(error_paths(&i0_abi, json!({ "x": 0, "f": 0 })).is_empty());
+        assert_eq!(error_paths(&i0_abi, json!({ "x": -1, "f": 0 })), vec!["x"]);
+        let i200_abi = integer("signed", 200);
+        assert!(error_paths(&i200_abi, json!({ "x": u128::MAX.to_string(), "f": 0 })).is_empty());
+    }
+
+    #[test]
+    fn test_validate_boolean_strings() {
+        let abi = abi::parse_abi(&json!({
+            "parameters": [{ "name": "flag", "type": { "kind": "boolean" }, "visibility": "private" }],
+            "return_type": null,
+            "error_types": {}
+        }))
+        .unwrap();
+
+        validation::validate_inputs(&abi, &json!({ "flag": "1" })).unwrap();
+        validation::validate_inputs(&abi, &json!({ "flag": true })).unwrap();
+        // noirc_abi only parses booleans given as strings from "0" and "1"
+        let errors = validation::validate_inputs(&abi, &json!({ "flag": "true" })).unwrap_err();
+        assert_eq!(errors[0].path, "flag");
+    }
+
+    #[test]
+    fn test_validate_witness_map() {
+        let product_circuit = compiled_circuit("product");
+        let program = product_circuit.program().unwrap();
+
+        let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+        validation::validate_witness_map(&program, &initial_witness).unwrap();
+
+        // b (witness 1) is missing and witness 5 is not a parameter
+        let mut invalid_witness = WitnessMap::new();
+        invalid_witness.insert(Witness(0), FieldElement::from(5_u128));
+        invalid_witness.insert(Witness(2), FieldElement::from(30_u128));
+        invalid_witness.insert(Witness(5), FieldElement::from(1_u128));
+        let errors = validation::validate_witness_map(&program, &invalid_witness).unwrap_err();
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, vec!["_1: missing witness value", "_5: unexpected witness"]);
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use acvm::{
+    acir::{
+        brillig::Opcode as BrilligOpcode,
+        circuit::{brillig::BrilligInputs, Circuit, Opcode, OpcodeLocation, Program},
+        native_types::{Witness, WitnessMap, WitnessStack},
+        FieldElement,
+    },
+    AcirField,
+};
+use noirc_errors::debug_info::{DebugInfo, DebugVarId, ProgramDebugInfo};
+use serde::Serialize;
+
+use super::{opcode_witnesses, witness_stack_items};
+use crate::abi::{witness_paths, Abi};
+use crate::debug::{opcode_call_stack, FileMap, SourceLocation};
+
+/// A witness whose value differs between two witness maps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WitnessDiffEntry {
+    /// The position of the witness map in the witness stack (0 for a single witness map)
+    pub stack_position: usize,
+    /// The id of the ACIR function the witness belongs to
+    pub function_id: u32,
+    /// The index of the witness
+    pub witness: u32,
+    /// The value on the left side, as a hexadecimal string (`None` if missing)
+    pub left: Option<String>,
+    /// The value on the right side, as a hexadecimal string (`None` if missing)
+    pub right: Option<String>,
+    /// The path of the main function parameter held by the witness, e.g. `msg[3]`
+    pub parameter: Option<String>,
+    /// The Noir variable first assigned the witness, e.g. `hash[0]`, if the circuit was
+    /// compiled with `--instrument-debug`
+    pub variable: Option<String>,
+    /// The Noir source of the first opcode constraining or assigning the witness, if debug symbols are available
+    pub source: Option<SourceLocation>,
+}
+
+/// The differences between two witness maps or witness stacks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WitnessDiff {
+    pub entries: Vec<WitnessDiffEntry>,
+    /// Set when the two witness stacks do not have the same number of items
+    /// (only the common items are compared)
+    pub stack_lengths: Option<(usize, usize)>,
+}
+
+impl WitnessDiff {
+    /// Whether the two sides are identical
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.stack_lengths.is_none()
+    }
+
+    /// Name the differing witnesses of the main function after the ABI parameters they hold
+    ///
+    /// # Arguments
+    ///
+    /// * abi: The ABI of the circuit
+    pub fn annotate_with_abi(&mut self, abi: &Abi) {
+        let paths = witness_paths(abi);
+        for entry in self.entries.iter_mut().filter(|entry| entry.function_id == 0) {
+            entry.parameter = paths.get(&entry.witness).cloned();
+        }
+    }
+
+    /// Map the differing witnesses to the Noir source of the first opcode constraining or assigning
+    /// them, and to the Noir variable first assigned them
+    ///
+    /// The debug symbols only list the `variables` of a function: the witnesses they are assigned
+    /// are only known from the `__debug_var_assign` calls inserted in the circuits compiled with
+    /// `nargo compile --instrument-debug`, so other circuits only get source locations. The
+    /// parameters of the main function are named by `annotate_with_abi`.
+    ///
+    /// # Arguments
+    ///
+    /// * program: The program the witnesses were solved for
+    /// * debug_symbols: The decoded debug symbols of the program (see `debug::decode_debug_symbols`)
+    /// * file_map: The source files of the circuit (see `debug::parse_file_map`)
+    pub fn annotate_with_debug_symbols(
+        &mut self,
+        program: &Program<FieldElement>,
+        debug_symbols: &ProgramDebugInfo,
+        file_map: &FileMap,
+    ) {
+        let debug_var_assigns = debug_var_assign_functions(program);
+        let mut first_uses: BTreeMap<u32, BTreeMap<Witness, usize>> = BTreeMap::new();
+        let mut variables: BTreeMap<u32, BTreeMap<Witness, String>> = BTreeMap::new();
+        for entry in self.entries.iter_mut() {
+            let function_id = entry.function_id as usize;
+            let (Some(function), Some(debug_info)) =
+                (program.functions.get(function_id), debug_symbols.debug_infos.get(function_id))
+            else {
+                continue;
+            };
+            let uses = first_uses.entry(entry.function_id).or_insert_with(|| {
+                let mut uses = BTreeMap::new();
+                for (index, opcode) in function.opcodes.iter().enumerate() {
+                    for witness in opcode_witnesses(opcode, false) {
+                        uses.entry(witness).or_insert(index);
+                    }
+                }
+                uses
+            });
+            if let Some(index) = uses.get(&Witness(entry.witness)) {
+                // The innermost frame of the call stack is the closest to the witness
+                entry.source = opcode_call_stack(debug_info, &OpcodeLocation::Acir(*index), file_map).pop();
+            }
+            entry.variable = variables
+                .entry(entry.function_id)
+                .or_insert_with(|| variable_witnesses(function, debug_info, &debug_var_assigns))
+                .get(&Witness(entry.witness))
+                .cloned();
+        }
+    }
+
+    /// Get the differences as JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for WitnessDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((left, right)) = self.stack_lengths {
+            writeln!(f, "witness stacks have different lengths: {} vs {}", left, right)?;
+        }
+        if self.entries.is_empty() {
+            return writeln!(f, "no differing witnesses");
+        }
+        for entry in &self.entries {
+            write!(f, "[{}] function {} _{}", entry.stack_position, entry.function_id, entry.witness)?;
+            if let Some(parameter) = &entry.parameter {
+                write!(f, " ({})", parameter)?;
+            }
+            if let Some(variable) = &entry.variable {
+                write!(f, " `{}`", variable)?;
+            }
+            write!(
+                f,
+                ": {} -> {}",
+                entry.left.as_deref().unwrap_or("<missing>"),
+                entry.right.as_deref().unwrap_or("<missing>")
+            )?;
+            if let Some(source) = &entry.source {
+                write!(f, " at {} `{}`", source, source.snippet)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two witness maps
+///
+/// # Arguments
+///
+/// * left: The first witness map
+/// * right: The second witness map
+///
+/// # Returns
+///
+/// The witnesses whose values differ, or that are missing on one side
+pub fn diff_witness_maps(left: &WitnessMap<FieldElement>, right: &WitnessMap<FieldElement>) -> WitnessDiff {
+    WitnessDiff { entries: diff_entries(0, 0, left, right), stack_lengths: None }
+}
+
+/// Compare two witness stacks item by item
+///
+/// # Arguments
+///
+/// * left: The first witness stack
+/// * right: The second witness stack
+///
+/// # Returns
+///
+/// The witnesses whose values differ, or that are missing on one side
+pub fn diff_witness_stacks(left: &WitnessStack<FieldElement>, right: &WitnessStack<FieldElement>) -> WitnessDiff {
+    let left_items = witness_stack_items(left);
+    let right_items = witness_stack_items(right);
+    let mut entries = Vec::new();
+
+    for (position, (left_item, right_item)) in left_items.iter().zip(&right_items).enumerate() {
+        if left_item.index != right_item.index {
+            // Different functions were called, every witness is considered different
+            entries.extend(diff_entries(position, left_item.index, &left_item.witness, &WitnessMap::new()));
+            entries.extend(diff_entries(position, right_item.index, &WitnessMap::new(), &right_item.witness));
+        } else {
+            entries.extend(diff_entries(position, left_item.index, &left_item.witness, &right_item.witness));
+        }
+    }
+    let stack_lengths = (left_items.len() != right_items.len()).then_some((left_items.len(), right_items.len()));
+
+    WitnessDiff { entries, stack_lengths }
+}
+
+fn diff_entries(
+    stack_position: usize,
+    function_id: u32,
+    left: &WitnessMap<FieldElement>,
+    right: &WitnessMap<FieldElement>,
+) -> Vec<WitnessDiffEntry> {
+    let left: BTreeMap<Witness, FieldElement> = left.clone().into_iter().collect();
+    let right: BTreeMap<Witness, FieldElement> = right.clone().into_iter().collect();
+    let mut witnesses: Vec<&Witness> = left.keys().chain(right.keys()).collect();
+    witnesses.sort();
+    witnesses.dedup();
+
+    witnesses
+        .into_iter()
+        .filter(|witness| left.get(witness) != right.get(witness))
+        .map(|witness| WitnessDiffEntry {
+            stack_position,
+            function_id,
+            witness: witness.witness_index(),
+            left: left.get(witness).map(|value| format!("0x{}", value.to_hex())),
+            right: right.get(witness).map(|value| format!("0x{}", value.to_hex())),
+            parameter: None,
+            variable: None,
+            source: None,
+        })
+        .collect()
+}
+
+/// The oracle through which the circuits compiled with `--instrument-debug` report the
+/// values assigned to their variables, as `__debug_var_assign(var_id, value)`
+const DEBUG_VAR_ASSIGN_ORACLE: &str = "__debug_var_assign";
+
+/// Get the ids of the unconstrained functions calling the `__debug_var_assign` oracle
+fn debug_var_assign_functions(program: &Program<FieldElement>) -> BTreeSet<u32> {
+    program
+        .unconstrained_functions
+        .iter()
+        .enumerate()
+        .filter(|(_, function)| {
+            function.bytecode.iter().any(
+                |opcode| matches!(opcode, BrilligOpcode::ForeignCall { function, .. } if function == DEBUG_VAR_ASSIGN_ORACLE),
+            )
+        })
+        .map(|(id, _)| id as u32)
+        .collect()
+}
+
+/// Map the witnesses of an ACIR function to the Noir variables first assigned them, from the
+/// calls to the `__debug_var_assign` oracle (the elements of arrays are named `name[i]`)
+fn variable_witnesses(
+    function: &Circuit<FieldElement>,
+    debug_info: &DebugInfo,
+    debug_var_assigns: &BTreeSet<u32>,
+) -> BTreeMap<Witness, String> {
+    let mut variables = BTreeMap::new();
+    for opcode in &function.opcodes {
+        let Opcode::BrilligCall { id, inputs, .. } = opcode else {
+            continue;
+        };
+        let [BrilligInputs::Single(var_id), value, ..] = inputs.as_slice() else {
+            continue;
+        };
+        if !debug_var_assigns.contains(&id.0) {
+            continue;
+        }
+        let Some(variable) = var_id
+            .to_const()
+            .and_then(|var_id| u32::try_from(var_id.to_u128()).ok())
+            .and_then(|var_id| debug_info.variables.get(&DebugVarId(var_id)))
+        else {
+            continue;
+        };
+        match value {
+            BrilligInputs::Single(expression) => {
+                if let Some(witness) = expression.to_witness() {
+                    variables.entry(witness).or_insert_with(|| variable.name.clone());
+                }
+            }
+            BrilligInputs::Array(expressions) => {
+                for (index, expression) in expressions.iter().enumerate() {
+                    if let Some(witness) = expression.to_witness() {
+                        variables.entry(witness).or_insert_with(|| format!("{}[{}]", variable.name, index));
+                    }
+                }
+            }
+            BrilligInputs::MemoryArray(_) => {}
+        }
+    }
+    variables
+}
@@ -0,0 +1,518 @@
+use std::{fs, path::Path};
+
+use acvm::acir::{
+    circuit::{
+        brillig::{BrilligInputs, BrilligOutputs},
+        Opcode, Program,
+    },
+    native_types::{Expression, StackItem, WitnessMap, WitnessStack, Witness},
+    FieldElement,
+};
+
+pub mod diff;
+#[cfg(test)]
+mod tests;
+
+pub use diff::{diff_witness_maps, diff_witness_stacks, WitnessDiff, WitnessDiffEntry};
+
+use crate::serialization::{compress, deserialize_any_format, serialize_with_format, SerializationFormat};
+use crate::validation::{format_input_errors, InputError};
+use crate::abi::{decode_public_values, parse_json_inputs, parse_toml_inputs, Abi, Format, InputMap, MAIN_RETURN_NAME};
+
+/// Convert a vector of field elements to a witness map
+/// 
+/// # Arguments
+/// 
+/// * witness_vec: The vector of field elements to convert to a witness map
+/// The actual type of the vector items can be either a FieldElement or an unsigned integer
+/// 
+/// # Returns
+/// 
+/// The witness map
+pub fn from_vec_to_witness_map<T>(witness_vec: Vec<T>) -> Result<WitnessMap<FieldElement>, String>
+where
+    T: Copy,
+    FieldElement: From<T>
+{
+    let mut witness_map = WitnessMap::new();
+
+    for (i, witness) in witness_vec.iter().enumerate() {
+        witness_map.insert(Witness(i as u32), FieldElement::from(*witness));
+    }
+
+    Ok(witness_map)
+}
+
+/// Convert a vector of strings to a witness map
+/// 
+/// # Arguments
+/// 
+/// * witness_vec: The vector of strings to convert to a witness map
+/// Each string is expected to be a valid hexadecimal or decimal string
+/// 
+/// # Returns
+/// 
+/// The witness map, or an error listing every string that is not a valid field element
+pub fn from_vec_str_to_witness_map(witness_vec: Vec<&str>) -> Result<WitnessMap<FieldElement>, String> {
+    let mut witness_map = WitnessMap::new();
+    let mut errors = Vec::new();
+
+    for (i, witness) in witness_vec.iter().enumerate() {
+        match FieldElement::try_from_str(witness) {
+            Some(value) => {
+                witness_map.insert(Witness(i as u32), value);
+            }
+            None => errors.push(InputError {
+                path: format!("[{}]", i),
+                message: format!("invalid field element \"{}\"", witness),
+            }),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format_input_errors(&errors));
+    }
+    Ok(witness_map)
+}
+
+/// Encode named inputs into a witness map following the ABI of the circuit
+///
+/// Structs, arrays, tuples, strings, booleans and integers are flattened in the
+/// order expected by the circuit, with signed integers encoded in two's complement.
+/// A `BoundedVec` can be given either as `{ "storage": [...], "len": n }` or as a plain array.
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit (see `abi::get_abi`)
+/// * inputs: A JSON object mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The witness map, or an error listing every invalid input (see `validation::validate_inputs`)
+pub fn from_json_to_witness_map(abi: &Abi, inputs: &serde_json::Value) -> Result<WitnessMap<FieldElement>, String> {
+    let input_map = parse_json_inputs(abi, inputs)?;
+    from_input_map_to_witness_map(abi, &input_map)
+}
+
+/// Encode named inputs written in TOML (e.g. the content of a `Prover.toml`)
+/// into a witness map following the ABI of the circuit
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit (see `abi::get_abi`)
+/// * inputs: The TOML string mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The witness map, or an error listing every invalid input (see `validation::validate_inputs`)
+pub fn from_toml_to_witness_map(abi: &Abi, inputs: &str) -> Result<WitnessMap<FieldElement>, String> {
+    let input_map = parse_toml_inputs(abi, inputs)?;
+    from_input_map_to_witness_map(abi, &input_map)
+}
+
+/// Encode an already parsed input map into a witness map following the ABI of the circuit
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * input_map: The input values of the circuit, keyed by parameter name
+///
+/// # Returns
+///
+/// The witness map
+pub fn from_input_map_to_witness_map(abi: &Abi, input_map: &InputMap) -> Result<WitnessMap<FieldElement>, String> {
+    let mut input_map = input_map.clone();
+    let return_value = input_map.remove(MAIN_RETURN_NAME);
+    abi.encode(&input_map, return_value).map_err(|e| format!("Failed to encode inputs: {}", e))
+}
+
+/// Read a `Prover.toml` file and encode its inputs into a witness map
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * path: The path to the `Prover.toml` file
+///
+/// # Returns
+///
+/// The witness map
+pub fn read_prover_toml(abi: &Abi, path: impl AsRef<Path>) -> Result<WitnessMap<FieldElement>, String> {
+    let path = path.as_ref();
+    let inputs = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    from_toml_to_witness_map(abi, &inputs)
+}
+
+/// Serialize named inputs into the content of a `Prover.toml` file
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: A JSON object mapping each parameter name to its value
+///
+/// # Returns
+///
+/// The TOML string
+pub fn to_prover_toml(abi: &Abi, inputs: &serde_json::Value) -> Result<String, String> {
+    let input_map = parse_json_inputs(abi, inputs)?;
+    Format::Toml.serialize(&input_map, abi).map_err(|e| format!("Failed to serialize inputs: {}", e))
+}
+
+/// Write named inputs to a `Prover.toml` file
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * inputs: A JSON object mapping each parameter name to its value
+/// * path: The path of the file to write
+pub fn write_prover_toml(abi: &Abi, inputs: &serde_json::Value, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    let content = to_prover_toml(abi, inputs)?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Serialize the public inputs and the return value of a solved witness
+/// into the content of a `Verifier.toml` file, as done by `nargo execute`
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * solved_witness: The solved witness map of the main function
+///
+/// # Returns
+///
+/// The TOML string
+pub fn to_verifier_toml(abi: &Abi, solved_witness: &WitnessMap<FieldElement>) -> Result<String, String> {
+    let (mut public_inputs, return_value) = decode_public_values(abi, solved_witness)?;
+    if let Some(return_value) = return_value {
+        public_inputs.insert(MAIN_RETURN_NAME.to_string(), return_value);
+    }
+    Format::Toml
+        .serialize(&public_inputs, &abi.clone().public_abi())
+        .map_err(|e| format!("Failed to serialize public inputs: {}", e))
+}
+
+/// Write the public inputs and the return value of a solved witness to a `Verifier.toml` file
+///
+/// # Arguments
+///
+/// * abi: The ABI of the circuit
+/// * solved_witness: The solved witness map of the main function
+/// * path: The path of the file to write
+pub fn write_verifier_toml(abi: &Abi, solved_witness: &WitnessMap<FieldElement>, path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    let content = to_verifier_toml(abi, solved_witness)?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Wrap the witness map into a witness stack
+/// 
+/// # Arguments
+/// 
+/// * witness_map: The witness map to wrap into a witness stack
+/// 
+/// # Returns
+/// 
+/// The witness stack
+pub fn witness_map_to_witness_stack(witness_map: WitnessMap<FieldElement>) -> Result<WitnessStack<FieldElement>, String> {
+    let witness_stack = WitnessStack::try_from(witness_map).map_err(|e| e.to_string())?;
+    Ok(witness_stack)
+}
+
+/// The solved witness of one ACIR function call in a witness stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionWitness {
+    /// The id of the ACIR function (0 being the main function)
+    pub function_id: u32,
+    /// The name of the function, if known
+    pub function_name: Option<String>,
+    /// The solved witness map of the call
+    pub witness: WitnessMap<FieldElement>,
+}
+
+/// Build a witness stack from the witness maps of several ACIR function calls
+///
+/// # Arguments
+///
+/// * witness_maps: The function ids and witness maps of each call, in execution order
+/// (i.e. callees before their caller), the main function coming last
+///
+/// # Returns
+///
+/// The witness stack
+pub fn witness_maps_to_witness_stack(
+    witness_maps: Vec<(u32, WitnessMap<FieldElement>)>,
+) -> Result<WitnessStack<FieldElement>, String> {
+    match witness_maps.last() {
+        Some((0, _)) => {}
+        Some((function_id, _)) => {
+            return Err(format!("The last witness map must belong to the main function, got function {}", function_id))
+        }
+        None => return Err("No witness maps provided".to_string()),
+    }
+    let mut witness_stack = WitnessStack::default();
+    for (function_id, witness_map) in witness_maps {
+        witness_stack.push(function_id, witness_map);
+    }
+    Ok(witness_stack)
+}
+
+/// Get the items of the witness stack in execution order, the main function coming last
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to iterate over
+///
+/// # Returns
+///
+/// The stack items
+pub fn witness_stack_items(witness_stack: &WitnessStack<FieldElement>) -> Vec<StackItem<FieldElement>> {
+    let mut witness_stack = witness_stack.clone();
+    let mut items = Vec::with_capacity(witness_stack.length());
+    while let Some(item) = witness_stack.pop() {
+        items.push(item);
+    }
+    items.reverse();
+    items
+}
+
+/// Get the witnesses of an opcode
+///
+/// # Arguments
+///
+/// * opcode: The opcode to inspect
+/// * call_inputs: Whether to include the inputs of Brillig and ACIR calls
+///
+/// # Returns
+///
+/// The witnesses the opcode constrains or assigns (every witness of an AssertZero and the
+/// outputs of the other opcodes), preceded by the inputs of the call if `call_inputs` is set
+pub(crate) fn opcode_witnesses(opcode: &Opcode<FieldElement>, call_inputs: bool) -> Vec<Witness> {
+    match opcode {
+        Opcode::AssertZero(expression) => expression_witnesses(expression),
+        Opcode::BlackBoxFuncCall(call) => call.get_outputs_vec(),
+        Opcode::BrilligCall { inputs, outputs, .. } => inputs
+            .iter()
+            .filter(|_| call_inputs)
+            .flat_map(|input| match input {
+                BrilligInputs::Single(expression) => expression_witnesses(expression),
+                BrilligInputs::Array(expressions) => expressions.iter().flat_map(expression_witnesses).collect(),
+                BrilligInputs::MemoryArray(_) => Vec::new(),
+            })
+            .chain(outputs.iter().flat_map(|output| match output {
+                BrilligOutputs::Simple(witness) => vec![*witness],
+                BrilligOutputs::Array(witnesses) => witnesses.clone(),
+            }))
+            .collect(),
+        Opcode::Call { inputs, outputs, .. } => inputs.iter().filter(|_| call_inputs).chain(outputs).copied().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn expression_witnesses(expression: &Expression<FieldElement>) -> Vec<Witness> {
+    expression
+        .mul_terms
+        .iter()
+        .flat_map(|(_, lhs, rhs)| [*lhs, *rhs])
+        .chain(expression.linear_combinations.iter().map(|(_, witness)| *witness))
+        .collect()
+}
+
+/// Get the solved witness of every ACIR function call in the witness stack
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to iterate over
+/// * function_names: The names of the ACIR functions, indexed by function id
+/// (the `names` field of the compiled circuit JSON)
+///
+/// # Returns
+///
+/// The function witnesses in execution order, the main function coming last
+pub fn witness_stack_functions(witness_stack: &WitnessStack<FieldElement>, function_names: &[String]) -> Vec<FunctionWitness> {
+    witness_stack_items(witness_stack)
+        .into_iter()
+        .map(|item| FunctionWitness {
+            function_id: item.index,
+            function_name: function_names.get(item.index as usize).cloned(),
+            witness: item.witness,
+        })
+        .collect()
+}
+
+/// Get the solved witness map of the main function
+///
+/// # Arguments
+///
+/// * witness_stack: The solved witness stack
+///
+/// # Returns
+///
+/// The witness map of the main function
+pub fn main_witness_map(witness_stack: &WitnessStack<FieldElement>) -> Result<WitnessMap<FieldElement>, String> {
+    match witness_stack.peek() {
+        Some(item) if item.index == 0 => Ok(item.witness.clone()),
+        Some(item) => Err(format!("The top of the witness stack belongs to function {}, not to main", item.index)),
+        None => Err("The witness stack is empty".to_string()),
+    }
+}
+
+/// Check that a solved witness stack is consistent with the program: every item refers to
+/// an existing ACIR function and holds the values of its parameters and return values,
+/// and the main function comes last
+///
+/// # Arguments
+///
+/// * program: The program the witness stack was solved for
+/// * witness_stack: The solved witness stack
+///
+/// # Returns
+///
+/// An error listing every problem found, if any
+pub fn validate_witness_stack(program: &Program<FieldElement>, witness_stack: &WitnessStack<FieldElement>) -> Result<(), String> {
+    let items = witness_stack_items(witness_stack);
+    let mut errors = Vec::new();
+
+    match items.last() {
+        Some(item) if item.index == 0 => {}
+        Some(item) => errors.push(format!("The last item belongs to function {}, not to main", item.index)),
+        None => errors.push("The witness stack is empty".to_string()),
+    }
+    for (position, item) in items.iter().enumerate() {
+        let Some(function) = program.functions.get(item.index as usize) else {
+            errors.push(format!("Item {} refers to unknown function {}", position, item.index));
+            continue;
+        };
+        let expected = function
+            .private_parameters
+            .iter()
+            .chain(function.public_parameters.0.iter())
+            .chain(function.return_values.0.iter());
+        for witness in expected {
+            if !item.witness.contains_key(witness) {
+                errors.push(format!(
+                    "Item {} (function {}) is missing witness {}",
+                    position,
+                    item.index,
+                    witness.witness_index()
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// Encode the witness stack in the given format, optionally gzip-compressed
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to encode
+/// * format: The serialization format to use
+/// * compressed: Whether to gzip the serialized witness stack
+///
+/// # Returns
+///
+/// The encoded witness stack
+pub fn encode_witness(
+    witness_stack: &WitnessStack<FieldElement>,
+    format: SerializationFormat,
+    compressed: bool,
+) -> Result<Vec<u8>, String> {
+    let buf = serialize_with_format(witness_stack, format)?;
+    if compressed {
+        compress(&buf)
+    } else {
+        Ok(buf)
+    }
+}
+
+/// Decode a witness stack, detecting its compression and serialization format
+///
+/// # Arguments
+///
+/// * buf: The encoded witness stack, as produced by `encode_witness`
+///
+/// # Returns
+///
+/// The witness stack and the format it was serialized with
+pub fn decode_witness(buf: &[u8]) -> Result<(WitnessStack<FieldElement>, SerializationFormat), String> {
+    deserialize_any_format(buf).map_err(|e| format!("Failed to decode witness stack: {}", e))
+}
+
+/// Serialize the witness stack in the msgpack-compact format expected by barretenberg
+///
+/// The result is the raw (uncompressed) serialized witness suitable for passing
+/// to barretenberg.
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to serialize
+///
+/// # Returns
+///
+/// The serialized witness stack
+pub fn serialize_witness(witness_stack: WitnessStack<FieldElement>) -> Result<Vec<u8>, String> {
+    encode_witness(&witness_stack, SerializationFormat::MsgpackCompact, false)
+}
+
+/// Deserialize the witness stack, whatever its format and compression
+/// 
+/// # Arguments
+/// 
+/// * serialized_witness_stack: The serialized witness stack to deserialize
+/// 
+/// # Returns
+/// 
+/// The witness stack
+pub fn deserialize_witness(serialized_witness_stack: Vec<u8>) -> Result<WitnessStack<FieldElement>, String> {
+    let (witness_stack, _) = decode_witness(&serialized_witness_stack)?;
+    Ok(witness_stack)
+}
+
+/// Load a compressed witness stack file, such as the `target/<name>.gz` file written by `nargo execute <name>`
+///
+/// # Arguments
+///
+/// * path: The path to the witness file
+///
+/// # Returns
+///
+/// The witness stack
+pub fn load_witness(path: impl AsRef<Path>) -> Result<WitnessStack<FieldElement>, String> {
+    let path = path.as_ref();
+    let buf = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (witness_stack, _) = decode_witness(&buf)?;
+    Ok(witness_stack)
+}
+
+/// Save a witness stack to a gzip-compressed file in the msgpack-compact format,
+/// which can be passed to `bb prove -w` like the files written by `nargo execute`
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to save
+/// * path: The path of the file to write
+pub fn save_witness(witness_stack: &WitnessStack<FieldElement>, path: impl AsRef<Path>) -> Result<(), String> {
+    save_witness_with_format(witness_stack, path, SerializationFormat::MsgpackCompact)
+}
+
+/// Save a witness stack to a gzip-compressed file in the given format
+///
+/// # Arguments
+///
+/// * witness_stack: The witness stack to save
+/// * path: The path of the file to write
+/// * format: The serialization format to use
+pub fn save_witness_with_format(
+    witness_stack: &WitnessStack<FieldElement>,
+    path: impl AsRef<Path>,
+    format: SerializationFormat,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    let buf = encode_witness(witness_stack, format, true)?;
+    fs::write(path, buf).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,217 @@
+use acvm::acir::{native_types::Witness, FieldElement};
+use serde_json::json;
+
+use crate::test_utils::{compiled_circuit, product_abi, sample_abi, TempPath};
+use crate::{execute, serialization::SerializationFormat, witness};
+
+#[test]
+fn test_from_json_to_witness_map() {
+    let abi = sample_abi();
+    let inputs = json!({
+        "x": -1,
+        "flag": true,
+        "name": "abc",
+        "items": ["0x01", "2"],
+        "result": "30"
+    });
+    let witness_map = witness::from_json_to_witness_map(&abi, &inputs).unwrap();
+
+    // Two's complement of -1 on 8 bits
+    assert_eq!(witness_map[&Witness(0)], FieldElement::from(255_u128));
+    assert_eq!(witness_map[&Witness(1)], FieldElement::from(1_u128));
+    assert_eq!(witness_map[&Witness(2)], FieldElement::from(b'a' as u128));
+    assert_eq!(witness_map[&Witness(4)], FieldElement::from(b'c' as u128));
+    // BoundedVec storage is padded with zeroes and followed by its length
+    assert_eq!(witness_map[&Witness(5)], FieldElement::from(1_u128));
+    assert_eq!(witness_map[&Witness(6)], FieldElement::from(2_u128));
+    assert_eq!(witness_map[&Witness(7)], FieldElement::from(0_u128));
+    assert_eq!(witness_map[&Witness(8)], FieldElement::from(2_u128));
+    assert_eq!(witness_map[&Witness(9)], FieldElement::from(30_u128));
+}
+
+#[test]
+fn test_from_toml_to_witness_map() {
+    let abi = sample_abi();
+    let inputs = r#"
+        x = "-2"
+        flag = false
+        name = "xyz"
+        result = "0x1e"
+
+        [items]
+        storage = ["1", "2", "3"]
+        len = "3"
+    "#;
+    let witness_map = witness::from_toml_to_witness_map(&abi, inputs).unwrap();
+
+    assert_eq!(witness_map[&Witness(0)], FieldElement::from(254_u128));
+    assert_eq!(witness_map[&Witness(1)], FieldElement::from(0_u128));
+    assert_eq!(witness_map[&Witness(8)], FieldElement::from(3_u128));
+    assert_eq!(witness_map[&Witness(9)], FieldElement::from(30_u128));
+}
+
+#[test]
+fn test_read_prover_toml() {
+    let abi = product_abi();
+    let witness_map = witness::read_prover_toml(&abi, "circuits/crates/product/Prover.toml").unwrap();
+    let expected = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    assert_eq!(witness_map, expected);
+}
+
+#[test]
+fn test_toml_files_round_trip() {
+    let abi = product_abi();
+    let inputs = json!({ "a": "5", "b": "6", "result": "30" });
+
+    let prover_toml = witness::to_prover_toml(&abi, &inputs).unwrap();
+    let witness_map = witness::from_toml_to_witness_map(&abi, &prover_toml).unwrap();
+    assert_eq!(witness_map, witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap());
+
+    // Only the public inputs end up in the Verifier.toml, as with `nargo execute`
+    let verifier_toml = witness::to_verifier_toml(&abi, &witness_map).unwrap();
+    let expected = std::fs::read_to_string("circuits/crates/product/Verifier.toml").unwrap();
+    assert_eq!(verifier_toml.trim(), expected.trim());
+}
+
+#[test]
+fn test_from_vec_str_to_witness_map_rejects_invalid_values() {
+    let error = witness::from_vec_str_to_witness_map(vec!["5", "0xzz", "0x1e", "abc"]).unwrap_err();
+    assert_eq!(error, "[1]: invalid field element \"0xzz\"\n[3]: invalid field element \"abc\"");
+}
+
+#[test]
+fn test_witness_codec_round_trip() {
+    let witness_map = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let witness_stack = witness::witness_map_to_witness_stack(witness_map).unwrap();
+
+    for format in [SerializationFormat::Bincode, SerializationFormat::Msgpack, SerializationFormat::MsgpackCompact] {
+        for compressed in [false, true] {
+            let encoded = witness::encode_witness(&witness_stack, format, compressed).unwrap();
+            let (decoded, detected_format) = witness::decode_witness(&encoded).unwrap();
+            assert_eq!(decoded, witness_stack);
+            assert_eq!(detected_format, format);
+        }
+    }
+
+    // The witness passed to the backend can be read back as is
+    let serialized = witness::serialize_witness(witness_stack.clone()).unwrap();
+    assert_eq!(witness::deserialize_witness(serialized).unwrap(), witness_stack);
+}
+
+#[test]
+fn test_save_and_load_witness() {
+    let witness_map = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let witness_stack = witness::witness_map_to_witness_stack(witness_map).unwrap();
+
+    let path = TempPath::new("product_witness.gz");
+    witness::save_witness(&witness_stack, &path).unwrap();
+    let buf = std::fs::read(&path).unwrap();
+    assert!(crate::serialization::is_compressed(&buf));
+
+    assert_eq!(witness::load_witness(&path).unwrap(), witness_stack);
+}
+
+#[test]
+fn test_multi_function_witness_stack() {
+    let callee_witness = witness::from_vec_to_witness_map(vec![2_u128, 3_u128, 6_u128]).unwrap();
+    let main_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let witness_stack = witness::witness_maps_to_witness_stack(vec![
+        (1, callee_witness.clone()),
+        (0, main_witness.clone()),
+    ])
+    .unwrap();
+
+    let names = vec!["main".to_string(), "product".to_string()];
+    let functions = witness::witness_stack_functions(&witness_stack, &names);
+    assert_eq!(functions.len(), 2);
+    assert_eq!(functions[0].function_id, 1);
+    assert_eq!(functions[0].function_name.as_deref(), Some("product"));
+    assert_eq!(functions[0].witness, callee_witness);
+    assert_eq!(functions[1].function_name.as_deref(), Some("main"));
+    assert_eq!(witness::main_witness_map(&witness_stack).unwrap(), main_witness);
+
+    // The main function must come last
+    assert!(witness::witness_maps_to_witness_stack(vec![(0, main_witness), (1, callee_witness)]).is_err());
+}
+
+#[test]
+fn test_validate_witness_stack() {
+    let product_circuit = compiled_circuit("product");
+    let program = product_circuit.program().unwrap();
+
+    let initial_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let witness_stack = execute::execute(&product_circuit, initial_witness).unwrap();
+    witness::validate_witness_stack(&program, &witness_stack).unwrap();
+
+    let incomplete_witness = witness::from_vec_to_witness_map(vec![5_u128, 6_u128]).unwrap();
+    let incomplete_stack = witness::witness_map_to_witness_stack(incomplete_witness).unwrap();
+    assert!(witness::validate_witness_stack(&program, &incomplete_stack).is_err());
+}
+
+#[test]
+fn test_diff_witness_maps() {
+    let left = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let right = witness::from_vec_to_witness_map(vec![5_u128, 7_u128, 35_u128, 1_u128]).unwrap();
+
+    let mut diff = witness::diff_witness_maps(&left, &right);
+    diff.annotate_with_abi(&product_abi());
+
+    let witnesses: Vec<u32> = diff.entries.iter().map(|entry| entry.witness).collect();
+    assert_eq!(witnesses, vec![1, 2, 3]);
+    assert_eq!(diff.entries[0].parameter.as_deref(), Some("b"));
+    assert_eq!(diff.entries[1].parameter.as_deref(), Some("result"));
+    assert_eq!(diff.entries[2].left, None);
+
+    let json = diff.to_json();
+    assert_eq!(json["entries"][1]["parameter"], "result");
+    assert!(diff.to_string().contains("function 0 _1 (b)"));
+
+    assert!(witness::diff_witness_maps(&left, &left).is_empty());
+}
+
+#[test]
+fn test_diff_annotate_with_debug_symbols() {
+    use acvm::acir::brillig::Opcode as BrilligOpcode;
+    use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligFunctionId, BrilligInputs};
+    use acvm::acir::circuit::Opcode;
+    use acvm::acir::native_types::Expression;
+    use noirc_errors::debug_info::{DebugTypeId, DebugVarId, DebugVariable};
+
+    let circuit = compiled_circuit("product");
+    let mut program = circuit.program().unwrap();
+    let mut debug_symbols = circuit.debug_info().unwrap().unwrap();
+
+    let left = witness::from_vec_to_witness_map(vec![5_u128, 6_u128, 30_u128]).unwrap();
+    let right = witness::from_vec_to_witness_map(vec![5_u128, 7_u128, 30_u128]).unwrap();
+    let mut diff = witness::diff_witness_maps(&left, &right);
+    diff.annotate_with_debug_symbols(&program, &debug_symbols, &circuit.file_map);
+    let source = diff.entries[0].source.as_ref().unwrap();
+    assert_eq!(source.snippet, "assert(a * b == result);");
+    assert_eq!(diff.entries[0].variable, None);
+
+    // Instrument the circuit as `--instrument-debug` does: `__debug_var_assign(0, b)`
+    let debug_var_assign = BrilligOpcode::ForeignCall {
+        function: "__debug_var_assign".to_string(),
+        destinations: Vec::new(),
+        destination_value_types: Vec::new(),
+        inputs: Vec::new(),
+        input_value_types: Vec::new(),
+    };
+    program.unconstrained_functions.push(BrilligBytecode { bytecode: vec![debug_var_assign], ..Default::default() });
+    program.functions[0].opcodes.push(Opcode::BrilligCall {
+        id: BrilligFunctionId(program.unconstrained_functions.len() as u32 - 1),
+        inputs: vec![
+            BrilligInputs::Single(Expression::from_field(FieldElement::from(0_u128))),
+            BrilligInputs::Single(Expression::from(Witness(1))),
+        ],
+        outputs: Vec::new(),
+        predicate: None,
+    });
+    debug_symbols.debug_infos[0]
+        .variables
+        .insert(DebugVarId(0), DebugVariable { name: "b".to_string(), debug_type_id: DebugTypeId(0) });
+
+    diff.annotate_with_debug_symbols(&program, &debug_symbols, &circuit.file_map);
+    assert_eq!(diff.entries[0].variable.as_deref(), Some("b"));
+    assert!(diff.to_string().contains("function 0 _1 `b`"), "{}", diff);
+}